@@ -16,6 +16,9 @@
 //!
 //! 4. Fallback as `impl std::error::Error`.
 //!
+//! 5. `no_std` support, by opting out the default feature "std". The "alloc"
+//!    feature enables the `Vec<Frame>` log agent without "std".
+//!
 //! # Examples
 //!
 //! ```rust
@@ -76,6 +79,11 @@
 //! }
 //! ```
 
+#![cfg_attr( all( not( feature="std" ), not( test )), no_std )]
+
+#[cfg( feature="alloc" )]
+extern crate alloc;
+
 use enumx::export::*;
 
 /// Enum exchange to wrap an `Err`.
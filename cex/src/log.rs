@@ -96,12 +96,24 @@
 //! Even if backtrace is disabled at compile time, these will compile. The second
 //! argument just has no effect.
 
-use std::{
-    env,
-    fmt::Debug,
+use core::{
+    fmt::{Debug, Display},
     marker::PhantomData,
+    ops::Deref,
 };
 
+#[cfg( feature="alloc" )]
+use alloc::{
+    borrow::Cow,
+    format,
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+#[cfg( feature="std" )]
+use std::env;
+
 /// Log agent.
 pub trait LogAgent {
     type Item;
@@ -111,6 +123,7 @@ pub trait LogAgent {
     fn append_log( &mut self, item: Self::Item );
 }
 
+#[cfg( feature="alloc" )]
 impl<T> LogAgent for Vec<T> {
     type Item = T;
 
@@ -127,6 +140,7 @@ impl<T> LogAgent for PhantomData<T> {
     fn append_log( &mut self, _item: T ) {}
 }
 
+#[cfg( feature="alloc" )]
 impl LogAgent for String {
     type Item = String;
 
@@ -135,9 +149,25 @@ impl LogAgent for String {
     fn append_log( &mut self, item: String ) { self.push_str( &format!( "\n{}", item )); }
 }
 
+/// The default log agent, which requires "alloc" feature.
+#[cfg( feature="alloc" )]
+pub type DefaultAgent = Vec<Frame>;
+
+/// The log agent which discards frames, for environments without "alloc" feature.
+pub type DiscardAgent = PhantomData<Frame>;
+
+/// A wrapper struct for logging error value.
+#[cfg( feature="alloc" )]
+#[derive( PartialEq,Eq )]
+pub struct Log<Inner, Agent: LogAgent = DefaultAgent> {
+    pub error : Inner, // the error
+    pub agent : Agent, // log agent
+}
+
 /// A wrapper struct for logging error value.
+#[cfg( not( feature="alloc" ))]
 #[derive( PartialEq,Eq )]
-pub struct Log<Inner, Agent: LogAgent = Vec<Frame>> {
+pub struct Log<Inner, Agent: LogAgent> {
     pub error : Inner, // the error
     pub agent : Agent, // log agent
 }
@@ -147,7 +177,7 @@ impl<Inner,Agent> Debug for Log<Inner,Agent>
     where Inner: Debug
         , Agent: Debug + LogAgent
 {
-    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+    fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
         f.debug_struct("Log")
          .field( "error", &self.error )
          .field( "agent", &self.agent )
@@ -160,7 +190,7 @@ impl<Inner,Agent> Debug for Log<Inner,Agent>
     where Inner: Debug
         , Agent: Debug + LogAgent
 {
-    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+    fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
         if f.alternate() {
             f.debug_struct("Log")
              .field( "error", &self.error )
@@ -250,9 +280,11 @@ impl_logger_for_predefined_enumx! {
 }
 
 /// Environment variable `RUST_BACKTRACE` controlled log agent.
+#[cfg( feature="std" )]
 #[derive( Debug, PartialEq, Eq )]
 pub struct Env<Agent: LogAgent>( Agent );
 
+#[cfg( feature="std" )]
 impl<Agent> LogAgent for Env<Agent>
     where Agent : LogAgent
 {
@@ -277,6 +309,7 @@ impl<Agent> LogAgent for Env<Agent>
     }
 }
 
+#[cfg( feature="std" )]
 fn env_log_enabled() -> bool {
     env::var( "RUST_BACKTRACE" )
         .map( |value| value == "1" || value == "full" )
@@ -363,6 +396,43 @@ impl<Agent,T,E> MapErrLog<Agent> for Result<T,E>
     }
 }
 
+#[cfg( feature="alloc" )]
+type FrameInfoRepr = Cow<'static,str>;
+
+#[cfg( not( feature="alloc" ))]
+type FrameInfoRepr = &'static str;
+
+/// The context info in a `Frame`, converted from `&'static str`, or from `String` if "alloc" feature is enabled.
+#[derive( Clone,Default,PartialEq,Eq,PartialOrd,Ord )]
+pub struct FrameInfo( FrameInfoRepr );
+
+impl FrameInfo {
+    pub fn as_str( &self ) -> &str { &self.0 }
+}
+
+impl Deref for FrameInfo {
+    type Target = str;
+
+    fn deref( &self ) -> &str { self.as_str() }
+}
+
+impl Debug for FrameInfo {
+    fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result { Debug::fmt( self.as_str(), f )}
+}
+
+impl Display for FrameInfo {
+    fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result { Display::fmt( self.as_str(), f )}
+}
+
+impl From<&'static str> for FrameInfo {
+    fn from( info: &'static str ) -> Self { FrameInfo( info.into() )}
+}
+
+#[cfg( feature="alloc" )]
+impl From<String> for FrameInfo {
+    fn from( info: String ) -> Self { FrameInfo( info.into() )}
+}
+
 /// A struct for store one frame for backtrace.
 #[derive( Debug,Default,PartialEq,Eq,PartialOrd,Ord )]
 pub struct Frame {
@@ -370,11 +440,11 @@ pub struct Frame {
     pub file   : &'static str,
     pub line   : u32,
     pub column : u32,
-    pub info   : Option<String>,
+    pub info   : Option<FrameInfo>,
 }
 
 impl Frame {
-    pub fn new( module: &'static str, file: &'static str, line: u32, column: u32, info: Option<String> ) -> Self {
+    pub fn new( module: &'static str, file: &'static str, line: u32, column: u32, info: Option<FrameInfo> ) -> Self {
         Frame{ module, file, line, column, info }
     }
 }
@@ -387,9 +457,9 @@ impl Frame {
 #[macro_export]
 macro_rules! frame {
     ( $expr:expr ) => {
-        Frame::new( module_path!(), file!(), line!(), column!(), Some( String::from( $expr )))
+        $crate::Frame::new( module_path!(), file!(), line!(), column!(), Some( ::core::convert::From::from( $expr )))
     };
     () => {
-        Frame::new( module_path!(), file!(), line!(), column!(), None )
+        $crate::Frame::new( module_path!(), file!(), line!(), column!(), None )
     };
 }
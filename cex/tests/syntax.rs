@@ -61,6 +61,55 @@ macro_rules! define_tests {
                 })
             }
 
+            #[cex] fn throws_a_call_bca( retry: bool ) -> Result!( () throws A ) {
+                throws_bca_call_ab().or_else( |err| #[ty_pat] match err {
+                    a @ A if !retry => throw!( a ),
+                    A(_) | B(_) | C(_) => ret!(),
+                })
+            }
+
+            #[cex] fn throws_ba_inspect_bca() -> Result!( bool throws B,C,A ) {
+                match throws_bca_call_ab() {
+                    Ok(_) => ret!( false ),
+                    Err( mut err ) => {
+                        #[ty_pat] match &mut err {
+                            B( ref mut b ) => { let _: &mut _ = b; },
+                            C(_) => (),
+                            A(_) => (),
+                        }
+                        let is_c = #[ty_pat] match &err {
+                            B(_) => false,
+                            C(_) => true,
+                            A(_) => false,
+                        };
+                        if is_c { ret!( true ); }
+                        Err( err )
+                    },
+                }
+            }
+
+            #[cex] fn throws_ba_inspect_bca_by_ref() -> Result!( bool throws B,C,A ) {
+                match throws_bca_call_ab() {
+                    Ok(_) => ret!( false ),
+                    Err( mut err ) => {
+                        let err_mut = &mut err;
+                        #[ty_pat] match err_mut {
+                            B( b ) => { let _: &mut _ = b; },
+                            C(_) => (),
+                            A(_) => (),
+                        }
+                        let err_ref = &err;
+                        let is_c = #[ty_pat] match err_ref {
+                            B(_) => false,
+                            C( c ) => { let _: &_ = c; true },
+                            A(_) => false,
+                        };
+                        if is_c { ret!( true ); }
+                        Err( err )
+                    },
+                }
+            }
+
             #[cfg( feature = "unstable" )]
             fn closure_throws_abc() {
                 let _f = #[cex] || -> Result!( i32 throws A,B,C ) { throw!(A); throw!(B); throw!(C); ret!(0); };
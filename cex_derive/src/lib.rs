@@ -29,6 +29,7 @@ extern crate proc_macro;
 use self::proc_macro::{TokenStream, TokenTree};
 
 use quote::{
+    ToTokens,
     quote,
     quote_spanned,
 };
@@ -48,6 +49,7 @@ use syn::{
     Token,
     Type,
     TypePath,
+    parse_macro_input,
     parse_quote,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
@@ -65,6 +67,7 @@ use indexmap::{
 
 use std::{
     cell::Cell,
+    fmt::Display,
     hash::{Hash, Hasher},
     iter::FromIterator,
 };
@@ -76,10 +79,41 @@ macro_rules! parse_quote_spanned {
     }};
 }
 
+// Collects errors of one macro invocation, to be reported all at once.
+#[derive( Default )]
+struct Errors( Option<syn::Error> );
+
+impl Errors {
+    fn push( &mut self, error: syn::Error ) {
+        match &mut self.0 {
+            Some( errors ) => errors.combine( error ),
+            None => self.0 = Some( error ),
+        }
+    }
+
+    fn error( &mut self, tokens: impl ToTokens, message: impl Display ) {
+        self.push( syn::Error::new_spanned( tokens, message ));
+    }
+
+    fn extend( &mut self, errors: Errors ) {
+        if let Some( error ) = errors.0 {
+            self.push( error );
+        }
+    }
+
+    fn is_empty( &self ) -> bool {
+        self.0.is_none()
+    }
+
+    fn to_compile_error( &self ) -> proc_macro2::TokenStream {
+        self.0.as_ref().map( syn::Error::to_compile_error ).unwrap_or_default()
+    }
+}
+
 /// Implements `cex::Logger` for an `enum`.
 #[proc_macro_derive( Logger )]
 pub fn derive_logger( input: TokenStream ) -> TokenStream {
-    let input: DeriveInput = syn::parse( input ).unwrap();
+    let input = parse_macro_input!( input as DeriveInput );
 
     match input.data {
         syn::Data::Enum( ref data ) => {
@@ -97,16 +131,19 @@ pub fn derive_logger( input: TokenStream ) -> TokenStream {
             let ( _, ty_generics, where_clause ) = input.generics.split_for_impl();
             let clause = where_clause.map( |where_clause| &where_clause.predicates );
 
-            let variant_ty = data.variants.iter().map( |ref v| {
+            let mut errors = Errors::default();
+            let variant_ty = data.variants.iter().filter_map( |v| {
                 if let syn::Fields::Unnamed( ref fields ) = v.fields {
-                    let mut iter = fields.unnamed.iter();
-                    if iter.len() == 1 {
-                        let field = iter.next().unwrap();
-                        return &field.ty;
+                    if fields.unnamed.len() == 1 {
+                        return Some( &fields.unnamed[0].ty );
                     }
                 }
-                panic!( "{}", "A type deriving `Logger` should be in the form of \"enum Name { Foo(Type), Bar(AnotherType),... }\"" );
-            });
+                errors.error( v, "A type deriving `Logger` should be in the form of \"enum Name { Foo(Type), Bar(AnotherType),... }\"" );
+                None
+            }).collect::<Vec<_>>();
+            if !errors.is_empty() {
+                return errors.to_compile_error().into();
+            }
 
             let expanded = quote! {
                 impl #impl_generics cex::Logger<Agent> for #name #ty_generics
@@ -125,7 +162,8 @@ pub fn derive_logger( input: TokenStream ) -> TokenStream {
             };
             expanded.into()
         }
-        _ => panic!( "Only `enum`s can derive `Logger`." ),
+        syn::Data::Struct( ref data ) => syn::Error::new_spanned( data.struct_token, "Only `enum`s can derive `Logger`." ).to_compile_error().into(),
+        syn::Data::Union( ref data ) => syn::Error::new_spanned( data.union_token, "Only `enum`s can derive `Logger`." ).to_compile_error().into(),
     }
 }
 
@@ -171,17 +209,6 @@ enum Logger {
     EnvOpt,
 }
 
-impl From<&'static str> for Logger {
-    fn from( name: &'static str ) -> Self {
-        match name {
-            "cex"         => Logger::None,
-            "cex_log"     => Logger::Static,
-            "cex_env_log" => Logger::EnvOpt,
-            _             => panic!("only cex, cex_log, cex_env_log are supported"),
-        }
-    }
-}
-
 struct Ret {
     throws : IndexSet<TypeIndex>,
     ty     : Type,
@@ -190,19 +217,20 @@ struct Ret {
 struct CexTag {
     logger : Logger,
     ret    : Option<Ret>,
+    errors : Errors,
 }
 
 impl CexTag {
     fn new( logger: Logger ) -> Self {
-        CexTag{ logger, ret: None }
+        CexTag{ logger, ret: None, errors: Errors::default() }
     }
 
-    fn parse_type_path_list( logger: Logger, input: TokenStream ) -> syn::Result<TypePathList> {
-        let mut types = syn::parse::<TypePathList>( input )?;
+    fn parse_type_path_list( logger: Logger, input: proc_macro2::TokenStream ) -> syn::Result<TypePathList> {
+        let mut types = syn::parse2::<TypePathList>( input )?;
         match logger {
             Logger::None   => (),
             Logger::Static => types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty> )),
-            Logger::EnvOpt => types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty, cex::Env<Vec<cex::Frame>>> )),
+            Logger::EnvOpt => types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty, cex::Env<cex::DefaultAgent>> )),
         }
         Ok( types )
     }
@@ -219,39 +247,93 @@ enum TyPatAttr {
     Gen( Throws ),
 }
 
-fn parse_ty_pat_attr( logger: Logger, attr: &Attribute ) -> Option<TyPatAttr> {
+fn parse_ty_pat_attr( logger: Logger, attr: &Attribute ) -> syn::Result<Option<TyPatAttr>> {
     if attr.path.leading_colon.is_none() && attr.path.segments.len() == 1 {
         if attr.path.segments.first().unwrap().ident == "ty_pat" {
-            let ts = TokenStream::from( attr.tokens.clone() );
-            let mut iter = ts.into_iter();
-            if let Some( TokenTree::Group( group )) = iter.next() {
+            let mut iter = attr.tokens.clone().into_iter();
+            if let Some( proc_macro2::TokenTree::Group( group )) = iter.next() {
                 let mut iter = group.stream().into_iter();
                 match iter.next() {
-                    Some( TokenTree::Ident( ident_throws )) => match ident_throws.to_string().as_str() {
+                    Some( proc_macro2::TokenTree::Ident( ident_throws )) => match ident_throws.to_string().as_str() {
                         "gen_throws" => {
-                            return Some( TyPatAttr::GenThrows );
+                            return Ok( Some( TyPatAttr::GenThrows ));
                         },
                         "gen" => {
                             let mut throws = IndexSet::new();
-                            let types = CexTag::parse_type_path_list( logger, TokenStream::from_iter( iter )).expect("type list");
+                            let types = CexTag::parse_type_path_list( logger, proc_macro2::TokenStream::from_iter( iter ))?;
                             if types.0.len() == 0 {
-                                return Some( TyPatAttr::GenThrows );
+                                return Ok( Some( TyPatAttr::GenThrows ));
                             } else {
                                 types.0.into_iter().for_each( |ty| { throws.insert( TypeIndex( ty, Cell::new(0) )); });
-                                return Some( TyPatAttr::Gen( throws ));
+                                return Ok( Some( TyPatAttr::Gen( throws )));
                             }
                         },
-                        _ => panic!("invalid #[ty_pat] argument: only #[ty_pat(gen_throws)] and #[ty_pat(gen)] are supported"),
+                        _ => return Err( syn::Error::new( ident_throws.span(),
+                            "invalid #[ty_pat] argument: only #[ty_pat(gen_throws)] and #[ty_pat(gen)] are supported" )),
                     },
-                    Some( _ ) => panic!("invalid #[ty_pat] argument"),
-                    None => return Some( TyPatAttr::None ),
+                    Some( tt ) => return Err( syn::Error::new( tt.span(), "invalid #[ty_pat] argument" )),
+                    None => return Ok( Some( TyPatAttr::None )),
                 }
             } else {
-                return Some( TyPatAttr::None );
+                return Ok( Some( TyPatAttr::None ));
             }
         }
     }
-    return None;
+    return Ok( None );
+}
+
+// Rewrites the pattern of a `#[ty_pat] match` arm into the one of the ad-hoc enum,
+// the variant of which is given by `variant` from the type.
+fn adhoc_arm_pat( pat: &Pat, errors: &mut Errors, variant: &mut dyn FnMut( &Path ) -> Path ) -> Pat {
+    match pat {
+        Pat::Wild(_) => pat.clone(),
+        Pat::Or( pat_or ) => {
+            let mut pat_or = pat_or.clone();
+            pat_or.cases.iter_mut().for_each( |case| *case = adhoc_arm_pat( case, errors, variant ));
+            Pat::Or( pat_or )
+        },
+        _ => match type_pat( pat ) {
+            Ok(( ty, value_pat )) => {
+                let path = variant( &ty );
+                parse_quote!( #path( #value_pat ))
+            },
+            Err( error ) => {
+                errors.push( error );
+                pat.clone()
+            },
+        },
+    }
+}
+
+// Splits a type pattern into the type and the pattern of its value:
+// `Type(value_pat)` for a value of `Type`, `Type(a, b)` or `Type{ .. }` destructuring `Type` itself,
+// `Type` for any value of `Type`, and `binding @ type_pattern` binding the value.
+fn type_pat( pat: &Pat ) -> syn::Result<( Path, Pat )> {
+    match pat {
+        Pat::TupleStruct( pat_tuple_struct ) if pat_tuple_struct.pat.elems.len() == 1 =>
+            Ok(( pat_tuple_struct.path.clone(), pat_tuple_struct.pat.elems[0].clone() )),
+        Pat::TupleStruct( pat_tuple_struct ) => Ok(( pat_tuple_struct.path.clone(), pat.clone() )),
+        Pat::Struct( pat_struct ) => Ok(( pat_struct.path.clone(), pat.clone() )),
+        Pat::Path( pat_path ) if pat_path.qself.is_none() => Ok(( pat_path.path.clone(), parse_quote!( _ ))),
+        Pat::Ident( pat_ident ) => match &pat_ident.subpat {
+            Some(( at, subpat )) => {
+                let ( ty, value_pat ) = type_pat( subpat )?;
+                let mut binding = pat_ident.clone();
+                binding.subpat = match value_pat {
+                    Pat::Wild(_) => None,
+                    value_pat => Some(( *at, Box::new( value_pat ))),
+                };
+                Ok(( ty, Pat::Ident( binding )))
+            },
+            None if pat_ident.by_ref.is_none() && pat_ident.mutability.is_none() => {
+                let ident = &pat_ident.ident;
+                Ok(( parse_quote!( #ident ), parse_quote!( _ )))
+            },
+            None => Err( syn::Error::new_spanned( pat_ident,
+                "#[cex] expects a type pattern after `@` in binding by ref/mut, e.g. `ref e @ Type(_)`" )),
+        },
+        _ => Err( syn::Error::new_spanned( pat, "#[cex] unsupported pattern in match arm" )),
+    }
 }
 
 impl VisitMut for CexTag {
@@ -263,23 +345,38 @@ impl VisitMut for CexTag {
             if mac.path.leading_colon.is_none() && mac.path.segments.len() == 1 {
                 let seg = mac.path.segments.first().unwrap();
                 if seg.arguments == PathArguments::None && seg.ident == "Result" {
-                    let ts = TokenStream::from( mac.tokens.clone() );
-                    let mut iter = ts.into_iter();
-                    let mut ok = TokenStream::new();
+                    let mut iter = mac.tokens.clone().into_iter();
+                    let mut ok = proc_macro2::TokenStream::new();
                     while let Some(tt) = iter.next() {
-                        if let TokenTree::Ident( ident ) = &tt {
-                            if ident.to_string() == "throws" {
+                        if let proc_macro2::TokenTree::Ident( ident ) = &tt {
+                            if ident == "throws" {
                                 break;
                             }
                         }
                         ok.extend( std::iter::once( tt ));
                     }
-                    let mut ok = syn::parse::<Type>( ok ).expect("Result!( OkType ... )");
+                    let ok = if ok.is_empty() {
+                        Err( syn::Error::new_spanned( mac, "expected `Result!( OkType ... )`" ))
+                    } else {
+                        syn::parse2::<Type>( ok )
+                    };
+                    let rest = proc_macro2::TokenStream::from_iter( iter );
+                    let types = CexTag::parse_type_path_list( self.logger, rest );
+                    let ( mut ok, types ) = match ( ok, types ) {
+                        ( Ok( ok ), Ok( types )) => ( ok, types ),
+                        ( ok, types ) => {
+                            // The invocation is replaced so that `Result!()` does not report the same error again.
+                            let mut errors = ok.err().into_iter().chain( types.err() ).flat_map( syn::Error::into_iter );
+                            if let Some( error ) = errors.next() {
+                                *node = Type::Verbatim( error.to_compile_error() );
+                            }
+                            errors.for_each( |error| self.errors.push( error ));
+                            return;
+                        },
+                    };
                     self.visit_type_mut( &mut ok );
 
                     let mut throws = IndexSet::new();
-                    let rest = TokenStream::from_iter( iter );
-                    let types = CexTag::parse_type_path_list( self.logger, rest ).expect("type list");
                     types.0.into_iter().for_each( |ty| {
                         let mut type_ = Type::Path( TypePath{ qself: None, path: ty });
                         self.visit_type_mut( &mut type_ );
@@ -308,89 +405,75 @@ impl VisitMut for CexTag {
                     self.visit_expr_mut( &mut *arm.body );
                 });
 
-                let mut ty_pat_attrs = None;
                 let logger = self.logger;
-                for (index, attr) in expr_match.attrs.iter().enumerate() {
-                    if let Some( attrs ) = parse_ty_pat_attr( logger, attr ) {
-                        ty_pat_attrs = Some(( attrs, index ));
-                        break;
-                    }
-                }
-                ty_pat_attrs.as_ref().map( |(_,index)| expr_match.attrs.remove( *index ));
+                let ty_pat_attr = expr_match.attrs.iter().enumerate()
+                    .find_map( |(index, attr)| parse_ty_pat_attr( logger, attr ).transpose().map( |ty_pat_attr| (index, ty_pat_attr) ));
+                let ty_pat_attrs = match ty_pat_attr {
+                    Some(( index, ty_pat_attr )) => {
+                        let attr = expr_match.attrs.remove( index );
+                        match ty_pat_attr {
+                            Ok( ty_pat_attr ) => Some(( ty_pat_attr, attr )),
+                            Err( error ) => return self.errors.push( error ),
+                        }
+                    },
+                    None => None,
+                };
 
                 if let Some( ty_pat_attrs ) = ty_pat_attrs {
-                    let match_expr = &*expr_match.expr;
-                    let match_span = match_expr.span();
-                    expr_match.expr = Box::new( parse_quote_spanned!( match_span => ::enumx::ExchangeFrom::exchange_from( #match_expr ) ));
+                    let mut errors = Errors::default();
                     let mut index = 0_u32;
-                    let checked = expr_match.arms.iter_mut().fold( IndexMap::<Path,Cell<u32>>::new(), |mut acc, arm| {
-                        let mut add_type_pattern = |path: &mut Path| {
-                            let mut nth = index;
-                            acc.entry( path.clone() )
-                                .and_modify( |n| { nth = n.get() })
-                                .or_insert( Cell::new( nth ));
-                            if nth == index { index += 1; }
-
-                            let _n = make_ident( &format!( "_{}", nth ));
-                            *path = parse_quote!{ __CexAdhocEnum::#_n };
-                        };
-
-                        match &mut arm.pat {
-                            Pat::TupleStruct( pat_tuple_struct ) => {
-                                if pat_tuple_struct.pat.elems.len() > 1 {
-                                    panic!("#[cex] supports tuple struct variant in newtype form only.");
-                                }
-                                add_type_pattern( &mut pat_tuple_struct.path );
-                                acc
-                            },
-                            Pat::Path( pat_path ) => {
-                                add_type_pattern( &mut pat_path.path );
-                                acc
-                            },
-                            Pat::Wild(_) if arm.guard.is_none() => {
-                                acc
-                            },
-                            _ => {
-                                let pat_ident;
-                                if let Pat::Ident( ident ) = &mut arm.pat {
-                                    if ident.by_ref.is_some() || ident.mutability.is_some() || ident.subpat.is_some() {
-                                        panic!("#[cex] dost not support ident in arm with ref/mut/sub pattern.");
-                                    } else {
-                                        pat_ident = Some( ident.clone() );
-                                    }
-                                } else {
-                                    panic!("#[cex] unsupported pattern in match arm");
-                                }
-
-                                pat_ident.map( |pat_ident| {
-                                    let ident = &pat_ident.ident;
-                                    let mut path: Path = parse_quote!( #ident );
-                                    add_type_pattern( &mut path );
-                                    arm.pat = parse_quote!( #path(_) );
-                                });
+                    let mut checked = IndexMap::<Path,Cell<u32>>::new();
+                    let pats = expr_match.arms.iter().map( |arm| adhoc_arm_pat( &arm.pat, &mut errors, &mut |path| {
+                        let mut nth = index;
+                        checked.entry( path.clone() )
+                            .and_modify( |n| { nth = n.get() })
+                            .or_insert( Cell::new( nth ));
+                        if nth == index { index += 1; }
+
+                        let _n = make_ident( &format!( "_{}", nth ));
+                        parse_quote!{ __CexAdhocEnum::#_n }
+                    })).collect::<Vec<_>>();
+
+                    // Matching an expression of `&err` or `&mut err` type binds the variants by reference,
+                    // which is only known by the syntax here if it is written as `&err` or `&mut err`.
+                    let borrowed = matches!( &*expr_match.expr, Expr::Reference(_) );
+                    if borrowed && ty_pat_attrs.0 != TyPatAttr::None {
+                        errors.error( &ty_pat_attrs.1, "#[ty_pat(gen_throws)] and #[ty_pat(gen ..)] are not supported in matching borrowed errors" );
+                    } else if self.ret.is_none() && ty_pat_attrs.0 != TyPatAttr::None {
+                        errors.error( &ty_pat_attrs.1, "#[ty_pat(gen_throws)] and #[ty_pat(gen ..)] expect the return type to be given by `Result!()`" );
+                    }
+                    if !errors.is_empty() {
+                        return self.errors.extend( errors );
+                    }
 
-                                acc
-                            },
-                        }
-                    });
+                    expr_match.arms.iter_mut().zip( pats ).for_each( |( arm, pat )| arm.pat = pat );
+                    let match_expr = &*expr_match.expr;
+                    let match_span = match_expr.span();
+                    expr_match.expr = parse_quote_spanned!( match_span =>
+                        ::enumx::ty_pat::with( #match_expr, |src, mode| {
+                            #[allow( unused_imports )]
+                            use ::enumx::ty_pat::{ByMutKind as _, ByRefKind as _, ByValueKind as _};
+                            mode.ty_pat_mode().exchange( src )
+                        })
+                    );
 
                     let checked = IndexSet::<TypeIndex>::from_iter( checked.clone().into_iter().map( |(t,i)| TypeIndex(t,i) ));
                     let logger = self.logger;
                     let unexhausted = match &ty_pat_attrs.0 {
                         TyPatAttr::None => Vec::new(),
-                        TyPatAttr::GenThrows => self.ret.as_ref().expect("Result!( OkType throws ... ) parsed").throws.difference( &checked ).collect::<Vec<_>>(),
+                        TyPatAttr::GenThrows => self.ret.as_ref().unwrap().throws.difference( &checked ).collect::<Vec<_>>(),
                         TyPatAttr::Gen( throws ) => throws.difference( &checked ).collect::<Vec<_>>(),
                     };
 
                     unexhausted.iter().for_each( |TypeIndex(_,i)| {
                         i.set( index );
                         let _n = make_ident( &format!( "_{}", index ));
-                        let ret_type = &self.ret.as_ref().expect("Result!( OkType throws ... ) parsed").ty;
+                        let ret_type = &self.ret.as_ref().unwrap().ty;
                         expr_match.arms.push(
                             match logger {
                                 Logger::None   => parse_quote_spanned!( match_span => __CexAdhocEnum::#_n(v) => cex::   Throw::<#ret_type,                       _>::throw(     v), ),
-                                Logger::Static => parse_quote_spanned!( match_span => __CexAdhocEnum::#_n(v) => cex::ThrowLog::<#ret_type, Vec<Frame>          , _>::throw_log( v, || frame!() ), ),
-                                Logger::EnvOpt => parse_quote_spanned!( match_span => __CexAdhocEnum::#_n(v) => cex::ThrowLog::<#ret_type, cex::Env<Vec<Frame>>, _>::throw_log( v, || frame!() ), ),
+                                Logger::Static => parse_quote_spanned!( match_span => __CexAdhocEnum::#_n(v) => cex::ThrowLog::<#ret_type, cex::DefaultAgent    , _>::throw_log( v, || frame!() ), ),
+                                Logger::EnvOpt => parse_quote_spanned!( match_span => __CexAdhocEnum::#_n(v) => cex::ThrowLog::<#ret_type, cex::Env<cex::DefaultAgent>, _>::throw_log( v, || frame!() ), ),
                             }
                         );
                         index += 1;
@@ -402,28 +485,37 @@ impl VisitMut for CexTag {
                     let (checked_types, checked_indices): (Vec<_>, Vec<_>) = checked.iter().map( |TypeIndex(t,i)| (t,i) ).unzip();
                     let checked_indices = checked_indices.iter().map( |n| make_ident( &format!( "_{}", n.get() )));
 
-                    let adhoc_enum = match logger {
-                        Logger::None => quote_spanned! { match_span =>
-                            #[derive( ::enumx::Exchange )]
-                            enum __CexAdhocEnum {
-                                #( #checked_indices( #checked_types ), )*
-                                #( #unexhausted_indices( #unexhausted_types ), )*
-                            }
-                        },
-                        Logger::Static => quote_spanned! { match_span =>
-                            #[derive( ::enumx::Exchange, cex_derive::Logger )]
-                            enum __CexAdhocEnum {
-                                #( #checked_indices( cex::Log<#checked_types> ), )*
-                                #( #unexhausted_indices( #unexhausted_types ), )*
-                            }
-                        },
-                        Logger::EnvOpt => quote_spanned! { match_span =>
-                            #[derive( ::enumx::Exchange, cex_derive::Logger )]
-                            enum __CexAdhocEnum {
-                                #( #checked_indices( cex::Log<#checked_types, cex::Env<Vec<cex::Frame>>> ), )*
-                                #( #unexhausted_indices( #unexhausted_types ), )*
-                            }
-                        },
+                    let checked_types = checked_types.into_iter().map( |ty| match logger {
+                        Logger::None   => quote!( #ty ),
+                        Logger::Static => quote!( cex::Log<#ty> ),
+                        Logger::EnvOpt => quote!( cex::Log<#ty, cex::Env<cex::DefaultAgent>> ),
+                    }).collect::<Vec<_>>();
+                    let unexhausted_types = unexhausted_types.into_iter().map( |ty| quote!( #ty )).collect::<Vec<_>>();
+                    let mode_predicates = checked_types.iter().chain( &unexhausted_types )
+                        .map( |ty| quote!( __CexMode: ::enumx::ty_pat::Mode<'__cex, #ty> ))
+                        .collect::<Vec<_>>();
+                    let checked_types = checked_types.iter().map( |ty| quote!( <__CexMode as ::enumx::ty_pat::Mode<'__cex, #ty>>::Variant ));
+                    let unexhausted_types = unexhausted_types.iter().map( |ty| quote!( <__CexMode as ::enumx::ty_pat::Mode<'__cex, #ty>>::Variant ));
+
+                    let adhoc_derives = match logger {
+                        Logger::None => quote!( ::enumx::FromVariant ),
+                        Logger::Static | Logger::EnvOpt => quote!( ::enumx::FromVariant, cex_derive::Logger ),
+                    };
+
+                    let adhoc_enum = quote_spanned! { match_span =>
+                        #[derive( #adhoc_derives )]
+                        enum __CexAdhocEnum<'__cex, __CexMode>
+                            where #( #mode_predicates, )*
+                        {
+                            #( #checked_indices( #checked_types ), )*
+                            #( #unexhausted_indices( #unexhausted_types ), )*
+                        }
+
+                        impl<'__cex, __CexMode> ::enumx::ty_pat::Adhoc for __CexAdhocEnum<'__cex, __CexMode>
+                            where #( #mode_predicates, )*
+                        {
+                            type Mode = __CexMode;
+                        }
                     };
 
                     *expr = syn::parse::<Expr>( quote_spanned!{ match_span => {
@@ -466,11 +558,21 @@ impl VisitMut for CexTag {
                                     }
                                 }
 
-                                let mut expr_list = syn::parse::<ExprList>( TokenStream::from( mac.tokens.clone() )).unwrap();
+                                let mut expr_list = match syn::parse2::<ExprList>( mac.tokens.clone() ) {
+                                    Ok( expr_list ) => expr_list,
+                                    Err( error ) => return self.errors.push( error ),
+                                };
                                 expr_list.0.iter_mut().for_each( |expr| self.visit_expr_mut( expr ));
+                                let ret_type = match &self.ret {
+                                    Some( ret ) => &ret.ty,
+                                    None => return self.errors.error( &mac,
+                                        format!( "{}!() expects the return type to be given by `Result!()`", name )),
+                                };
+                                if self.logger != Logger::None && expr_list.0.len() > 2 {
+                                    return self.errors.error( &mac.tokens, "ret!()/throw!() should contain 1 or 2 argument(s)" );
+                                }
                                 let mut exprs = expr_list.0.into_iter();
                                 let logger = self.logger;
-                                let ret_type = &self.ret.as_ref().expect("Result!( OkType throws ... ) parsed").ty;
                                 let span = mac.tokens.span();
                                 *expr = match logger {
                                     Logger::None => {
@@ -482,7 +584,7 @@ impl VisitMut for CexTag {
                                         }
                                     },
                                     Logger::Static => {
-                                        let agent = quote!( Vec<Frame> );
+                                        let agent = quote!( cex::DefaultAgent );
                                         match exprs.len() {
                                             0 | 1 => {
                                                 let the_expr = exprs.next().unwrap_or_else( || parse_quote_spanned!( span => () ));
@@ -502,11 +604,11 @@ impl VisitMut for CexTag {
                                                     parse_quote_spanned!{ span => return cex::ThrowLog::<#ret_type,#agent,_>::throw_log( #the_expr, #the_log )}
                                                 }
                                             },
-                                            _ => panic!("1 ret!()/throw!() should contain 1 or 2 argument(s)"),
+                                            _ => unreachable!(),
                                         }
                                     },
                                     Logger::EnvOpt => {
-                                        let agent = quote!( cex::Env<Vec<Frame>> );
+                                        let agent = quote!( cex::Env<cex::DefaultAgent> );
                                         match exprs.len() {
                                             0 | 1 => {
                                                 let the_expr = exprs.next().unwrap_or_else( || parse_quote_spanned!( span => () ));
@@ -526,7 +628,7 @@ impl VisitMut for CexTag {
                                                     parse_quote_spanned!{ span => return cex::ThrowLog::<#ret_type,#agent,_>::throw_log( #the_expr, #the_log )}
                                                 }
                                             },
-                                            _ => panic!("2 ret!()/throw!() should contain 1 or 2 argument(s)"),
+                                            _ => unreachable!(),
                                         }
                                     },
                                 };
@@ -554,7 +656,7 @@ impl VisitMut for CexTag {
 /// 3. generate code for `Result!()`/`ret!()`/`throw!()` macro invocations.
 #[proc_macro_attribute]
 pub fn cex( _args: TokenStream, input: TokenStream ) -> TokenStream {
-    expand_cex( "cex", _args, input )
+    expand_cex( Logger::None, _args, input )
 }
 
 /// tag an `fn` with `#[cex_log]` to:
@@ -568,7 +670,7 @@ pub fn cex( _args: TokenStream, input: TokenStream ) -> TokenStream {
 /// 4. backtrace enabled unconditionally.
 #[proc_macro_attribute]
 pub fn cex_log( _args: TokenStream, input: TokenStream ) -> TokenStream {
-    expand_cex( "cex_log", _args, input )
+    expand_cex( Logger::Static, _args, input )
 }
 
 /// tag an `fn` with `#[cex_env_log]` to:
@@ -582,32 +684,40 @@ pub fn cex_log( _args: TokenStream, input: TokenStream ) -> TokenStream {
 /// 4. backtrace enabled depending on the environment variable `RUST_BACKTRACE`.
 #[proc_macro_attribute]
 pub fn cex_env_log( _args: TokenStream, input: TokenStream ) -> TokenStream {
-    expand_cex( "cex_env_log", _args, input )
+    expand_cex( Logger::EnvOpt, _args, input )
 }
 
-fn expand_cex( tag_name: &'static str, _args: TokenStream, input: TokenStream ) -> TokenStream {
+fn expand_cex( logger: Logger, _args: TokenStream, input: TokenStream ) -> TokenStream {
     if let Ok( mut item_fn ) = syn::parse::<ItemFn>( input.clone() ) {
-        let mut cex_tag = CexTag::new( Logger::from( tag_name ));
+        let mut cex_tag = CexTag::new( logger );
 
         cex_tag.visit_signature_mut( &mut item_fn.sig );
         cex_tag.visit_block_mut( &mut *item_fn.block );
-        let expanded = quote_spanned!( item_fn.span() => #item_fn );
+        let errors = cex_tag.errors.to_compile_error();
+        let expanded = quote_spanned!( item_fn.span() => #item_fn #errors );
         return TokenStream::from( expanded );
     } else if let Ok( mut expr_closure ) = syn::parse::<ExprClosure>( input.clone() ) {
-        let mut cex_tag = CexTag::new( Logger::from( tag_name ));
+        let mut cex_tag = CexTag::new( logger );
         cex_tag.visit_return_type_mut( &mut expr_closure.output );
         cex_tag.visit_expr_mut( &mut *expr_closure.body );
-        let expanded = quote_spanned!( expr_closure.span() => #expr_closure );
+        let expanded = if cex_tag.errors.is_empty() {
+            quote_spanned!( expr_closure.span() => #expr_closure )
+        } else {
+            let errors = cex_tag.errors.to_compile_error();
+            quote_spanned!( expr_closure.span() => { #errors #expr_closure })
+        };
         return TokenStream::from( expanded );
-    } else if let Ok( mut stmt ) = syn::parse::<Stmt>( input ) {
+    } else if let Ok( mut stmt ) = syn::parse::<Stmt>( input.clone() ) {
         if let Stmt::Local(_) = &stmt {
-            let mut cex_tag = CexTag::new( Logger::from( tag_name ));
+            let mut cex_tag = CexTag::new( logger );
             visit_mut::visit_stmt_mut( &mut cex_tag, &mut stmt );
-            let expanded = quote_spanned!( stmt.span() => #stmt );
+            let errors = cex_tag.errors.to_compile_error();
+            let expanded = quote_spanned!( stmt.span() => #errors #stmt );
             return TokenStream::from( expanded );
         }
     }
-    panic!( "#[cex] for functions, closures and try blocks only" );
+    let input = proc_macro2::TokenStream::from( input );
+    syn::Error::new_spanned( input, "#[cex] for functions, closures and try blocks only" ).to_compile_error().into()
 }
 
 /// # `Result!()` macro
@@ -662,9 +772,20 @@ pub fn Result( input: TokenStream ) -> TokenStream {
         }
         ok.extend( std::iter::once( tt ));
     }
-    let ok = syn::parse::<Type>( ok ).expect("Result!( OkType ... )");
-
+    let ok = if ok.is_empty() {
+        Err( syn::Error::new( Span::call_site(), "expected `Result!( OkType ... )`" ))
+    } else {
+        syn::parse::<Type>( ok )
+    };
     let throws = collect_throws( TokenStream::from_iter( iter ));
+    let ( ok, throws ) = match ( ok, throws ) {
+        ( Ok( ok ), Ok( throws )) => ( ok, throws ),
+        ( ok, throws ) => {
+            let mut errors = Errors::default();
+            ok.err().into_iter().chain( throws.err() ).for_each( |error| errors.push( error ));
+            return errors.to_compile_error().into();
+        },
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Result<#ok, Enum!(#(#err),*)> );
@@ -684,9 +805,20 @@ pub fn ResultLog( input: TokenStream ) -> TokenStream {
         }
         ok.extend( std::iter::once( tt ));
     }
-    let ok = syn::parse::<Type>( ok ).expect("Result!( OkType ... )");
-
-    let throws = collect_throws( TokenStream::from_iter( iter ));
+    let ok = if ok.is_empty() {
+        Err( syn::Error::new( Span::call_site(), "expected `Result!( OkType ... )`" ))
+    } else {
+        syn::parse::<Type>( ok )
+    };
+    let throws = collect_throws_log( TokenStream::from_iter( iter ));
+    let ( ok, throws ) = match ( ok, throws ) {
+        ( Ok( ok ), Ok( throws )) => ( ok, throws ),
+        ( ok, throws ) => {
+            let mut errors = Errors::default();
+            ok.err().into_iter().chain( throws.err() ).for_each( |error| errors.push( error ));
+            return errors.to_compile_error().into();
+        },
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Result<#ok, Enum!(#(#err),*)> );
@@ -706,9 +838,20 @@ pub fn ResultEnvLog( input: TokenStream ) -> TokenStream {
         }
         ok.extend( std::iter::once( tt ));
     }
-    let ok = syn::parse::<Type>( ok ).expect("Result!( OkType ... )");
-
-    let throws = collect_throws( TokenStream::from_iter( iter ));
+    let ok = if ok.is_empty() {
+        Err( syn::Error::new( Span::call_site(), "expected `Result!( OkType ... )`" ))
+    } else {
+        syn::parse::<Type>( ok )
+    };
+    let throws = collect_throws_env_log( TokenStream::from_iter( iter ));
+    let ( ok, throws ) = match ( ok, throws ) {
+        ( Ok( ok ), Ok( throws )) => ( ok, throws ),
+        ( ok, throws ) => {
+            let mut errors = Errors::default();
+            ok.err().into_iter().chain( throws.err() ).for_each( |error| errors.push( error ));
+            return errors.to_compile_error().into();
+        },
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Result<#ok, Enum!(#(#err),*)> );
@@ -720,7 +863,10 @@ pub fn ResultEnvLog( input: TokenStream ) -> TokenStream {
 #[proc_macro]
 #[allow( non_snake_case )]
 pub fn Throws( input: TokenStream ) -> TokenStream {
-    let throws = collect_throws( input );
+    let throws = match collect_throws( input ) {
+        Ok( throws ) => throws,
+        Err( error ) => return error.to_compile_error().into(),
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Enum!(#(#err),*) );
@@ -732,7 +878,10 @@ pub fn Throws( input: TokenStream ) -> TokenStream {
 #[proc_macro]
 #[allow( non_snake_case )]
 pub fn ThrowsLog( input: TokenStream ) -> TokenStream {
-    let throws = collect_throws_log( input );
+    let throws = match collect_throws_log( input ) {
+        Ok( throws ) => throws,
+        Err( error ) => return error.to_compile_error().into(),
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Enum!(#(#err),*) );
@@ -744,16 +893,19 @@ pub fn ThrowsLog( input: TokenStream ) -> TokenStream {
 #[proc_macro]
 #[allow( non_snake_case )]
 pub fn ThrowsEnvLog( input: TokenStream ) -> TokenStream {
-    let throws = collect_throws_env_log( input );
+    let throws = match collect_throws_env_log( input ) {
+        Ok( throws ) => throws,
+        Err( error ) => return error.to_compile_error().into(),
+    };
     let err = throws.iter().map( |type_index| &type_index.0 );
 
     let expanded = quote!( Enum!(#(#err),*) );
     expanded.into()
 }
 
-fn collect_throws( input: TokenStream ) -> IndexSet<TypeIndex> {
+fn collect_throws( input: TokenStream ) -> syn::Result<IndexSet<TypeIndex>> {
     let mut throws = IndexSet::new();
-    let types = syn::parse::<TypePathList>( input ).expect("type list");
+    let types = syn::parse::<TypePathList>( input )?;
     types.0.into_iter().for_each( |ty| {
         let type_ = Type::Path( TypePath{ qself: None, path: ty });
         match type_ {
@@ -762,12 +914,12 @@ fn collect_throws( input: TokenStream ) -> IndexSet<TypeIndex> {
         }
     });
 
-    throws
+    Ok( throws )
 }
 
-fn collect_throws_log( input: TokenStream ) -> IndexSet<TypeIndex> {
+fn collect_throws_log( input: TokenStream ) -> syn::Result<IndexSet<TypeIndex>> {
     let mut throws = IndexSet::new();
-    let mut types = syn::parse::<TypePathList>( input ).expect("type list");
+    let mut types = syn::parse::<TypePathList>( input )?;
     types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty> ));
 
     types.0.into_iter().for_each( |ty| {
@@ -778,13 +930,13 @@ fn collect_throws_log( input: TokenStream ) -> IndexSet<TypeIndex> {
         }
     });
 
-    throws
+    Ok( throws )
 }
 
-fn collect_throws_env_log( input: TokenStream ) -> IndexSet<TypeIndex> {
+fn collect_throws_env_log( input: TokenStream ) -> syn::Result<IndexSet<TypeIndex>> {
     let mut throws = IndexSet::new();
-    let mut types = syn::parse::<TypePathList>( input ).expect("type list");
-    types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty, cex::Env<Vec<cex::Frame>>> ));
+    let mut types = syn::parse::<TypePathList>( input )?;
+    types.0.iter_mut().for_each( |ty| *ty = parse_quote_spanned!( ty.span() => Log<#ty, cex::Env<cex::DefaultAgent>> ));
 
     types.0.into_iter().for_each( |ty| {
         let type_ = Type::Path( TypePath{ qself: None, path: ty });
@@ -794,5 +946,5 @@ fn collect_throws_env_log( input: TokenStream ) -> IndexSet<TypeIndex> {
         }
     });
 
-    throws
+    Ok( throws )
 }
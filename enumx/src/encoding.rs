@@ -0,0 +1,247 @@
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>
+
+//! # Serde support
+//!
+//! Enabled by the "serde" feature. An `Exchange`-able enum, i.e. a predefined
+//! one or a user-defined one deriving `Exchange`, can be encoded in one of the
+//! following ways:
+//!
+//! 1. untagged, e.g. `Enum2::<i32,String>::_1( "text".to_owned() )` as `"text"`.
+//!
+//! 2. variant-index tagged, e.g. the same value as `{"_1":"text"}`.
+//!
+//! 3. type_name-tagged, e.g. the same value as `{"alloc::string::String":"text"}`.
+//!
+//! Predefined enums implement `Serialize`/`Deserialize` in variant-index tagged
+//! encoding. Other encodings, or enums deriving `Exchange`, are available by
+//! wrapper types, or by modules for `#[serde( with = "..." )]`.
+//!
+//! ```rust
+//! use enumx::export::*;
+//! use enumx::predefined::*;
+//! use enumx::encoding::{TypeTagged, Untagged};
+//!
+//! let value = Enum2::<i32,String>::_1( "text".to_owned() );
+//! assert_eq!( serde_json::to_string( &value ).unwrap(), r#"{"_1":"text"}"# );
+//! assert_eq!( serde_json::to_string( &Untagged( &value )).unwrap(), r#""text""# );
+//!
+//! let Untagged( decoded ) = serde_json::from_str::<Untagged<Enum2<i32,String>>>( "42" ).unwrap();
+//! assert_eq!( decoded, Enum2::_0( 42 ));
+//!
+//! #[derive( Exchange, Debug, PartialEq, serde::Serialize, serde::Deserialize )]
+//! enum Value {
+//!     Int( i32 ),
+//!     Text( String ),
+//! }
+//!
+//! #[derive( serde::Serialize, serde::Deserialize )]
+//! struct Message {
+//!     #[serde( with = "enumx::encoding::type_tagged" )]
+//!     value: Value,
+//! }
+//!
+//! let json = serde_json::to_string( &Message{ value: Value::Int(7) }).unwrap();
+//! assert_eq!( json, r#"{"value":{"i32":7}}"# );
+//!
+//! let TypeTagged( decoded ) = serde_json::from_str::<TypeTagged<Value>>( r#"{"i32":7}"# ).unwrap();
+//! assert_eq!( decoded, Value::Int(7) );
+//! ```
+//!
+//! Note that untagged decoding tries the variants in order, and the first
+//! succeeded one wins. And type names are not guaranteed to be stable between
+//! compiler versions, as documented in `core::any::type_name()`.
+
+use crate::{AsProto, Proto};
+
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, EnumAccess, VariantAccess, Visitor},
+    ser::{Serialize, Serializer},
+    Deserialize,
+};
+
+use serde_value::Value;
+
+/// Encodings of enums.
+#[doc( hidden )]
+#[derive( Clone, Copy, Debug, PartialEq, Eq )]
+pub enum Tagging {
+    Untagged,
+    Index,
+    TypeName,
+}
+
+/// Serializes a prototype enum of borrowed variants.
+#[doc( hidden )]
+pub trait SerializeProto {
+    fn serialize_proto<S: Serializer>( &self, serializer: S, tagging: Tagging ) -> Result<S::Ok, S::Error>;
+}
+
+/// Deserializes a prototype enum.
+#[doc( hidden )]
+pub trait DeserializeProto<'de>: Sized {
+    const NAME: &'static str;
+    const VARIANTS: &'static [&'static str];
+
+    fn type_index( type_name: &str ) -> Option<u32>;
+    fn deserialize_variant<A: VariantAccess<'de>>( index: u32, variant: A ) -> Result<Self, A::Error>;
+    fn deserialize_untagged<E: de::Error>( value: Value ) -> Result<Self, E>;
+}
+
+#[doc( hidden )]
+pub fn serialize_variant<S, T>( serializer: S, tagging: Tagging, name: &'static str, index: u32, variant: &'static str, value: &T )
+    -> Result<S::Ok, S::Error>
+    where S: Serializer
+        , T: ?Sized + Serialize
+{
+    match tagging {
+        Tagging::Untagged => value.serialize( serializer ),
+        Tagging::Index    => serializer.serialize_newtype_variant( name, index, variant, value ),
+        Tagging::TypeName => serializer.serialize_newtype_variant( name, index, core::any::type_name::<T>(), value ),
+    }
+}
+
+#[doc( hidden )]
+pub fn deserialize_proto<'de, D, P>( deserializer: D, tagging: Tagging ) -> Result<P, D::Error>
+    where D: Deserializer<'de>
+        , P: DeserializeProto<'de>
+{
+    let visitor = ProtoVisitor{ tagging, marker: PhantomData };
+    match tagging {
+        Tagging::Untagged => P::deserialize_untagged( Value::deserialize( deserializer )? ),
+        Tagging::Index    => deserializer.deserialize_enum( P::NAME, P::VARIANTS, visitor ),
+        Tagging::TypeName => deserializer.deserialize_enum( P::NAME, &[], visitor ),
+    }
+}
+
+struct ProtoVisitor<P> {
+    tagging : Tagging,
+    marker  : PhantomData<P>,
+}
+
+impl<'de, P> Visitor<'de> for ProtoVisitor<P>
+    where P: DeserializeProto<'de>
+{
+    type Value = P;
+
+    fn expecting( &self, formatter: &mut fmt::Formatter ) -> fmt::Result {
+        write!( formatter, "enum {}", P::NAME )
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>( self, data: A ) -> Result<P, A::Error> {
+        let ( index, variant ) = data.variant_seed( VariantTag{ tagging: self.tagging, marker: PhantomData::<P> })?;
+        P::deserialize_variant( index, variant )
+    }
+}
+
+struct VariantTag<P> {
+    tagging : Tagging,
+    marker  : PhantomData<P>,
+}
+
+impl<'de, P> DeserializeSeed<'de> for VariantTag<P>
+    where P: DeserializeProto<'de>
+{
+    type Value = u32;
+
+    fn deserialize<D: Deserializer<'de>>( self, deserializer: D ) -> Result<u32, D::Error> {
+        deserializer.deserialize_identifier( self )
+    }
+}
+
+impl<'de, P> Visitor<'de> for VariantTag<P>
+    where P: DeserializeProto<'de>
+{
+    type Value = u32;
+
+    fn expecting( &self, formatter: &mut fmt::Formatter ) -> fmt::Result {
+        match self.tagging {
+            Tagging::TypeName => formatter.write_str( "type name of a variant" ),
+            _                 => formatter.write_str( "variant identifier" ),
+        }
+    }
+
+    fn visit_u64<E: de::Error>( self, index: u64 ) -> Result<u32, E> {
+        if self.tagging == Tagging::Index && index < P::VARIANTS.len() as u64 {
+            Ok( index as u32 )
+        } else {
+            Err( E::invalid_value( de::Unexpected::Unsigned( index ), &self ))
+        }
+    }
+
+    fn visit_str<E: de::Error>( self, tag: &str ) -> Result<u32, E> {
+        let index = match self.tagging {
+            Tagging::TypeName => P::type_index( tag ),
+            _                 => P::VARIANTS.iter().position( |variant| *variant == tag ).map( |index| index as u32 ),
+        };
+        index.ok_or_else( || E::unknown_variant( tag, P::VARIANTS ))
+    }
+}
+
+macro_rules! def_encoding {
+    ($(#[$meta:meta])* $wrapper:ident $module:ident $tagging:ident) => {
+        $(#[$meta])*
+        ///
+        /// Serializes `&Enum` and deserializes `Enum`.
+        #[derive( Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash )]
+        pub struct $wrapper<E>( pub E );
+
+        impl<'a, E> Serialize for $wrapper<&'a E>
+            where E       : AsProto<'a>
+                , E::Type : SerializeProto
+        {
+            fn serialize<S: Serializer>( &self, serializer: S ) -> Result<S::Ok, S::Error> {
+                $module::serialize( self.0, serializer )
+            }
+        }
+
+        impl<'de, E> Deserialize<'de> for $wrapper<E>
+            where E       : Proto
+                , E::Type : DeserializeProto<'de>
+        {
+            fn deserialize<D: Deserializer<'de>>( deserializer: D ) -> Result<Self, D::Error> {
+                $module::deserialize( deserializer ).map( $wrapper )
+            }
+        }
+
+        $(#[$meta])*
+        ///
+        /// Works with `#[serde( with = "..." )]`.
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<'a, E, S>( value: &'a E, serializer: S ) -> Result<S::Ok, S::Error>
+                where E       : AsProto<'a>
+                    , E::Type : SerializeProto
+                    , S       : Serializer
+            {
+                value.as_proto().serialize_proto( serializer, Tagging::$tagging )
+            }
+
+            pub fn deserialize<'de, E, D>( deserializer: D ) -> Result<E, D::Error>
+                where E       : Proto
+                    , E::Type : DeserializeProto<'de>
+                    , D       : Deserializer<'de>
+            {
+                deserialize_proto::<D, E::Type>( deserializer, Tagging::$tagging ).map( E::from_proto )
+            }
+        }
+    };
+}
+
+def_encoding! {
+    /// Encodes the variant only, without any tag.
+    Untagged untagged Untagged
+}
+
+def_encoding! {
+    /// Encodes the variant tagged with its index, e.g. `{"_1":value}` in JSON.
+    IndexTagged index_tagged Index
+}
+
+def_encoding! {
+    /// Encodes the variant tagged with its type name, e.g. `{"i32":value}` in JSON.
+    TypeTagged type_tagged TypeName
+}
@@ -4,6 +4,11 @@
 //! enumx = ENUM eXtensions.
 //!
 //! See the [enumx book](https://oooutlk.github.io/enumx/) for more.
+//!
+//! This library is `no_std` if the default feature "std" is opted out, without
+//! implementations of `std::error::Error` for predefined enums.
+
+#![cfg_attr( all( not( feature="std" ), not( test )), no_std )]
 
 #![cfg_attr( feature="unstable", feature(
     fn_traits,
@@ -16,6 +21,15 @@
 #[macro_use]
 pub mod macros;
 
+#[cfg( feature="serde" )]
+pub mod encoding;
+
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
 /// Reorganize types, traits and macros to export to end users.
 ///
 /// Two categories:
@@ -30,18 +44,34 @@ pub mod macros;
 pub mod export {
     pub mod exchange {
         pub use crate::{
+            Accept,
+            AsMutProto,
+            AsProto,
+            ByType,
             EnumToEnum,
+            EnumVisitor,
             ExchangeFrom,
             ExchangeInto,
+            Flatten,
             FromVariant,
+            GetVariant,
             IntoEnum,
+            MapVariant,
+            Narrow,
+            Tagged,
+            TryExchangeFrom,
+            TryExchangeInto,
             TyPat,
         };
         pub use enumx_derive::{
+            Coprod,
             Enum,
             Exchange,
+            Flat,
             FromVariant as _,
+            Minus,
             Proto,
+            Union,
             def_impls,
             enumx,
         };
@@ -50,6 +80,7 @@ pub mod export {
         pub mod derives {
             pub use enumx_derive::{
                 def_impls,
+                delegate,
                 sum,
                 sum_err,
             };
@@ -60,6 +91,31 @@ pub mod export {
             impl_super_traits,
             impl_all_traits,
         };
+        #[doc( hidden )]
+        pub use crate::{
+            __enumx_std_Borrow as Borrow,
+            __enumx_std_BorrowMut as BorrowMut,
+            __enumx_std_Error as Error,
+            __enumx_std_Binary as Binary,
+            __enumx_std_Display as Display,
+            __enumx_std_LowerHex as LowerHex,
+            __enumx_std_Octal as Octal,
+            __enumx_std_Pointer as Pointer,
+            __enumx_std_UpperHex as UpperHex,
+            __enumx_std_Future as Future,
+            __enumx_std_Hasher as Hasher,
+            __enumx_std_BufRead as BufRead,
+            __enumx_std_Read as Read,
+            __enumx_std_Seek as Seek,
+            __enumx_std_FusedIterator as FusedIterator,
+            __enumx_std_TrustedLen as TrustedLen,
+            __enumx_std_Deref as Deref,
+            __enumx_std_DerefMut as DerefMut,
+            __enumx_std_Generator as Generator,
+            __enumx_std_Index as Index,
+            __enumx_std_IndexMut as IndexMut,
+            __enumx_std_RangeBounds as RangeBounds,
+        };
     }
 
     pub use exchange::*;
@@ -67,11 +123,16 @@ pub mod export {
 }
 
 pub use enumx_derive::{
+    Coprod,
     Enum,
     Exchange,
+    Flat,
     FromVariant,
+    Minus,
     Proto,
+    Union,
     def_impls,
+    delegate,
     enumx,
     sum,
     sum_err,
@@ -113,9 +174,229 @@ impl<Src, Dest, Index> ExchangeInto<Dest, Index> for Src
     }
 }
 
+/// Converts an enum into another one composed of a subset of its variants, or gives back an enum composed of the rest variants.
+pub trait TryExchangeFrom<Src, Index>: Sized {
+    type Remainder;
+
+    fn try_exchange_from( src: Src ) -> Result<Self, Self::Remainder>;
+}
+
+/// Converts an enum into another one composed of a subset of its variants, or gives back an enum composed of the rest variants.
+pub trait TryExchangeInto<Dest, Index> {
+    type Remainder;
+
+    fn try_exchange_into( self ) -> Result<Dest, Self::Remainder>;
+}
+
+impl<Src, Dest, Index> TryExchangeInto<Dest, Index> for Src
+    where Dest: TryExchangeFrom<Src, Index>,
+{
+    type Remainder = <Dest as TryExchangeFrom<Src, Index>>::Remainder;
+
+    fn try_exchange_into( self ) -> Result<Dest, Self::Remainder> {
+        Dest::try_exchange_from( self )
+    }
+}
+
+/// Extracts one of its variants from an enum, or gives back an enum composed of the rest variants.
+pub trait Narrow<Variant, Index> {
+    type Remainder;
+
+    fn narrow( self ) -> Result<Variant, Self::Remainder>;
+
+    /// Extracts the variant, or exchanges the rest variants into another enum.
+    fn narrow_into<Rest, RestIndex>( self ) -> Result<Variant, Rest>
+        where Self           : Sized
+            , Self::Remainder: ExchangeInto<Rest, RestIndex>
+    {
+        self.narrow().map_err( |remainder| remainder.exchange_into() )
+    }
+}
+
+/// Accesses one of its variants of an enum.
+pub trait GetVariant<Variant, Index>: Sized {
+    fn get_variant( &self ) -> Option<&Variant>;
+    fn get_variant_mut( &mut self ) -> Option<&mut Variant>;
+    fn take_variant( self ) -> Result<Variant, Self>;
+}
+
+/// Marks the types of variants accessible by `ByType`, i.e. predefined enums, enums deriving `Exchange` and coproducts.
+pub trait HasVariants {}
+
+/// Accesses variants of an enum by their types, e.g. `value.is_variant::<i32>()`.
+///
+/// The methods are not named `is`/`get`/`get_mut`/`take`, which would be ambiguous with methods of other traits the
+/// enum may implement, e.g. `Iterator::take()`, once both traits are imported by `use enumx::export::*`.
+pub trait ByType<Index>: HasVariants {
+    /// Checks if the enum holds a variant of type `T`.
+    fn is_variant<T>( &self ) -> bool
+        where Self: GetVariant<T, Index>
+    {
+        GetVariant::<T, Index>::get_variant( self ).is_some()
+    }
+
+    /// Returns a reference to the variant of type `T`, if any.
+    fn get_by_type<T>( &self ) -> Option<&T>
+        where Self: GetVariant<T, Index>
+    {
+        GetVariant::<T, Index>::get_variant( self )
+    }
+
+    /// Returns a mutable reference to the variant of type `T`, if any.
+    fn get_by_type_mut<T>( &mut self ) -> Option<&mut T>
+        where Self: GetVariant<T, Index>
+    {
+        GetVariant::<T, Index>::get_variant_mut( self )
+    }
+
+    /// Extracts the variant of type `T`, or gives back the enum.
+    fn take_by_type<T>( self ) -> Result<T, Self>
+        where Self: GetVariant<T, Index>
+    {
+        GetVariant::<T, Index>::take_variant( self )
+    }
+
+    /// Maps the variant of type `T` into type `U`, keeping the other variants.
+    fn map_variant<T, U>( self, f: impl FnOnce( T ) -> U ) -> <Self as MapVariant<T, U, Index>>::Output
+        where Self: MapVariant<T, U, Index> + Sized
+    {
+        MapVariant::<T, U, Index>::map_variant_with( self, f )
+    }
+}
+
+// Bounded by `GetVariant` in each method rather than `Proto`, which enums with skipped variants do not implement.
+impl<Enum, Index> ByType<Index> for Enum where Enum: HasVariants {}
+
+/// Maps one of its variants of an enum into another type, keeping the other variants.
+pub trait MapVariant<Variant, Mapped, Index> {
+    type Output;
+
+    fn map_variant_with<F>( self, f: F ) -> Self::Output
+        where F: FnOnce( Variant ) -> Mapped;
+}
+
+/// Flattens nested enums into one enum composed of all the variants of them, e.g. `Enum!(A, Enum!(B,C))` into
+/// `Enum!(A,B,C)`, the type of which can be named by `Flat!()`.
+pub trait Flatten<Dest, Index> {
+    fn flatten( self ) -> Dest;
+}
+
+impl<Src, Dest, Index> Flatten<Dest, EnumToEnum<Index>> for Src
+    where Dest: ExchangeFrom<Src, EnumToEnum<Index>>,
+{
+    fn flatten( self ) -> Dest {
+        Dest::exchange_from( self )
+    }
+}
+
+/// Visits a variant of an enum, for each variant type of which it should be implemented, with the same `Output`.
+///
+/// A generic implementation, e.g. `impl<T: Debug> EnumVisitor<T> for Logger`, visits any enum the variants of which
+/// satisfy the bound.
+pub trait EnumVisitor<Variant> {
+    type Output;
+    fn visit( self, variant: Variant ) -> Self::Output;
+}
+
+/// Accepts a visitor for the variant, implemented for predefined enums and the ones deriving `Exchange`.
+///
+/// To visit borrowed variants, use `enum_value.as_proto().accept( visitor )`.
+pub trait Accept<Visitor> {
+    type Output;
+    fn accept( self, visitor: Visitor ) -> Self::Output;
+}
+
 /// Used in `ExchangeFrom`/`ExchangeInto` to distinguish conversions between enums from those between an enum and its variant.
 pub struct EnumToEnum<Index>( Index );
 
+/// A variant labeled by a `Tag` type, making variants of the same type distinguishable in exchanges.
+///
+/// A variant of a user-defined enum marked with `#[exchange( tag = Tag )]` is exchanged as `Tagged<Tag, T>` rather
+/// than `T`, so that `A( String )` and `B( String )` are different variants.
+#[repr( transparent )]
+pub struct Tagged<Tag, T> {
+    pub value : T,
+    tag       : PhantomData<fn() -> Tag>,
+}
+
+impl<Tag, T> Tagged<Tag, T> {
+    /// Labels the value by `Tag`.
+    pub fn new( value: T ) -> Self {
+        Tagged{ value, tag: PhantomData }
+    }
+
+    /// Labels the borrowed value by `Tag`.
+    pub fn from_ref( value: &T ) -> &Self {
+        // SAFETY: `Tagged<Tag, T>` is a transparent wrapper of `T`.
+        unsafe{ &*( value as *const T as *const Self )}
+    }
+
+    /// Labels the mutably borrowed value by `Tag`.
+    pub fn from_mut( value: &mut T ) -> &mut Self {
+        // SAFETY: `Tagged<Tag, T>` is a transparent wrapper of `T`.
+        unsafe{ &mut *( value as *mut T as *mut Self )}
+    }
+
+    /// Removes the label.
+    pub fn into_inner( self ) -> T {
+        self.value
+    }
+}
+
+impl<Tag, T: Clone> Clone for Tagged<Tag, T> {
+    fn clone( &self ) -> Self {
+        Tagged::new( self.value.clone() )
+    }
+}
+
+impl<Tag, T: Copy> Copy for Tagged<Tag, T> {}
+
+impl<Tag, T: fmt::Debug> fmt::Debug for Tagged<Tag, T> {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        f.debug_tuple( "Tagged" ).field( &self.value ).finish()
+    }
+}
+
+impl<Tag, T: PartialEq> PartialEq for Tagged<Tag, T> {
+    fn eq( &self, other: &Self ) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<Tag, T: Eq> Eq for Tagged<Tag, T> {}
+
+impl<Tag, T: PartialOrd> PartialOrd for Tagged<Tag, T> {
+    fn partial_cmp( &self, other: &Self ) -> Option<core::cmp::Ordering> {
+        self.value.partial_cmp( &other.value )
+    }
+}
+
+impl<Tag, T: Ord> Ord for Tagged<Tag, T> {
+    fn cmp( &self, other: &Self ) -> core::cmp::Ordering {
+        self.value.cmp( &other.value )
+    }
+}
+
+impl<Tag, T: Hash> Hash for Tagged<Tag, T> {
+    fn hash<H: Hasher>( &self, state: &mut H ) {
+        self.value.hash( state );
+    }
+}
+
+#[cfg( feature="serde" )]
+impl<Tag, T: serde::Serialize> serde::Serialize for Tagged<Tag, T> {
+    fn serialize<S: serde::Serializer>( &self, serializer: S ) -> Result<S::Ok, S::Error> {
+        self.value.serialize( serializer )
+    }
+}
+
+#[cfg( feature="serde" )]
+impl<'de, Tag, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Tagged<Tag, T> {
+    fn deserialize<D: serde::Deserializer<'de>>( deserializer: D ) -> Result<Self, D::Error> {
+        T::deserialize( deserializer ).map( Tagged::new )
+    }
+}
+
 /// Indicates the prototype for a user-defined `Exchange`-able enum.
 pub trait Proto {
     type Type;
@@ -123,6 +404,18 @@ pub trait Proto {
     fn into_proto( self ) -> Self::Type;
 }
 
+/// Borrows an `Exchange`-able enum as its prototype of borrowed variants, e.g. `&Enum!(A,B)` as `__2<&A,&B>`.
+pub trait AsProto<'a> {
+    type Type;
+    fn as_proto( &'a self ) -> Self::Type;
+}
+
+/// Mutably borrows an `Exchange`-able enum as its prototype of mutably borrowed variants, e.g. `&mut Enum!(A,B)` as `__2<&mut A,&mut B>`.
+pub trait AsMutProto<'a> {
+    type Type;
+    fn as_mut_proto( &'a mut self ) -> Self::Type;
+}
+
 /// # Predefined ad-hoc enums
 ///
 /// This library has defined `Enum0`, `Enum1` .. up to `Enum16` by default.
@@ -171,24 +464,72 @@ pub mod predefined {
 
         impl_trait!{ _impl!(T) AsRef<T> _for!( Enum![1..=16] )}
         impl_trait!{ _impl!(T) AsMut<T> _for!( Enum![1..=16] )}
+        impl_trait!{ _impl!(T) AsRef<[T]> _for!( Enum![1..=16] )}
+        impl_trait!{ _impl!(T) AsMut<[T]> _for!( Enum![1..=16] )}
         impl_trait!{ DoubleEndedIterator _for!( Enum![1..=16] )}
         impl_trait!{ ExactSizeIterator _for!( Enum![1..=16] )}
         impl_trait!{ _impl!(A) Extend<A> _for!( Enum![1..=16] )}
         impl_trait!{ Iterator _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::Display _for!( Enum![1..=16] )}
+        impl_trait!{ core::iter::FusedIterator _for!( Enum![1..=16] )}
+        impl_trait!{ core::ops::Deref _for!( Enum![1..=16] )}
+        impl_trait!{ core::ops::DerefMut _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::Binary _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::LowerHex _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::Octal _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::Pointer _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::UpperHex _for!( Enum![1..=16] )}
+        impl_trait!{ core::fmt::Write _for!( Enum![1..=16] )}
+        impl_trait!{ core::future::Future _for!( Enum![1..=16] )}
+        impl_trait!{ core::hash::Hash _for!( Enum![1..=16] )}
+        impl_trait!{ core::hash::Hasher _for!( Enum![1..=16] )}
+
+        #[cfg( feature="std" )]
         impl_trait!{ std::error::Error _for!( Enum![1..=16] )}
-        impl_trait!{ std::fmt::Display _for!( Enum![1..=16] )}
-        impl_trait!{ std::iter::FusedIterator _for!( Enum![1..=16] )}
-        impl_trait!{ std::ops::Deref _for!( Enum![1..=16] )}
-        impl_trait!{ std::ops::DerefMut _for!( Enum![1..=16] )}
+
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_core::Stream _for!( Enum![1..=16] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncBufRead _for!( Enum![1..=16] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncRead _for!( Enum![1..=16] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncWrite _for!( Enum![1..=16] )}
+
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncBufRead _for!( Enum![1..=16] )}
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncRead _for!( Enum![1..=16] )}
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncWrite _for!( Enum![1..=16] )}
 
         #[cfg( feature="unstable" )]
         crate::impl_all_traits!{ _impl!(Args) Fn<Args> _for!( Enum![1..=16] )}
 
         #[cfg( feature="unstable" )]
-        impl_trait!{ std::iter::TrustedLen _for!( Enum![1..=16] )}
+        impl_trait!{ core::iter::TrustedLen _for!( Enum![1..=16] )}
 
         #[cfg( feature="unstable" )]
-        impl_trait!{ _impl!(R) std::ops::Generator<R> _for!( Enum![1..=16] )}
+        impl_trait!{ _impl!(R) core::ops::Generator<R> _for!( Enum![1..=16] )}
+
+        #[cfg( feature="serde" )]
+        def_impls! {
+            impl serde::Serialize for Enum![1..=16]
+                where _Variants!(): serde::Serialize
+            {
+                fn serialize<S: serde::Serializer>( &self, serializer: S ) -> Result<S::Ok, S::Error> {
+                    crate::encoding::index_tagged::serialize( self, serializer )
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for Enum![1..=16]
+                where _Variants!(): serde::Deserialize<'de>
+            {
+                fn deserialize<D: serde::Deserializer<'de>>( deserializer: D ) -> Result<Self, D::Error> {
+                    crate::encoding::index_tagged::deserialize( deserializer )
+                }
+            }
+        }
     }
 
     #[cfg( feature="enum32" )]
@@ -197,24 +538,72 @@ pub mod predefined {
 
         impl_trait!{ _impl!(T) AsRef<T> _for!( Enum![17..=32] )}
         impl_trait!{ _impl!(T) AsMut<T> _for!( Enum![17..=32] )}
+        impl_trait!{ _impl!(T) AsRef<[T]> _for!( Enum![17..=32] )}
+        impl_trait!{ _impl!(T) AsMut<[T]> _for!( Enum![17..=32] )}
         impl_trait!{ DoubleEndedIterator _for!( Enum![17..=32] )}
         impl_trait!{ ExactSizeIterator _for!( Enum![17..=32] )}
         impl_trait!{ _impl!(A) Extend<A> _for!( Enum![17..=32] )}
         impl_trait!{ Iterator _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::Display _for!( Enum![17..=32] )}
+        impl_trait!{ core::iter::FusedIterator _for!( Enum![17..=32] )}
+        impl_trait!{ core::ops::Deref _for!( Enum![17..=32] )}
+        impl_trait!{ core::ops::DerefMut _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::Binary _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::LowerHex _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::Octal _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::Pointer _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::UpperHex _for!( Enum![17..=32] )}
+        impl_trait!{ core::fmt::Write _for!( Enum![17..=32] )}
+        impl_trait!{ core::future::Future _for!( Enum![17..=32] )}
+        impl_trait!{ core::hash::Hash _for!( Enum![17..=32] )}
+        impl_trait!{ core::hash::Hasher _for!( Enum![17..=32] )}
+
+        #[cfg( feature="std" )]
         impl_trait!{ std::error::Error _for!( Enum![17..=32] )}
-        impl_trait!{ std::fmt::Display _for!( Enum![17..=32] )}
-        impl_trait!{ std::iter::FusedIterator _for!( Enum![17..=32] )}
-        impl_trait!{ std::ops::Deref _for!( Enum![17..=32] )}
-        impl_trait!{ std::ops::DerefMut _for!( Enum![17..=32] )}
+
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_core::Stream _for!( Enum![17..=32] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncBufRead _for!( Enum![17..=32] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncRead _for!( Enum![17..=32] )}
+        #[cfg( feature="futures" )]
+        impl_trait!{ futures_io::AsyncWrite _for!( Enum![17..=32] )}
+
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncBufRead _for!( Enum![17..=32] )}
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncRead _for!( Enum![17..=32] )}
+        #[cfg( feature="tokio" )]
+        impl_trait!{ tokio::io::AsyncWrite _for!( Enum![17..=32] )}
 
         #[cfg( feature="unstable" )]
         crate::impl_all_traits!{ _impl!(Args) Fn<Args> _for!( Enum![17..=32] )}
 
         #[cfg( feature="unstable" )]
-        impl_trait!{ std::iter::TrustedLen _for!( Enum![17..=32] )}
+        impl_trait!{ core::iter::TrustedLen _for!( Enum![17..=32] )}
 
         #[cfg( feature="unstable" )]
-        impl_trait!{ _impl!(R) std::ops::Generator<R> _for!( Enum![17..=32] )}
+        impl_trait!{ _impl!(R) core::ops::Generator<R> _for!( Enum![17..=32] )}
+
+        #[cfg( feature="serde" )]
+        def_impls! {
+            impl serde::Serialize for Enum![17..=32]
+                where _Variants!(): serde::Serialize
+            {
+                fn serialize<S: serde::Serializer>( &self, serializer: S ) -> Result<S::Ok, S::Error> {
+                    crate::encoding::index_tagged::serialize( self, serializer )
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for Enum![17..=32]
+                where _Variants!(): serde::Deserialize<'de>
+            {
+                fn deserialize<D: serde::Deserializer<'de>>( deserializer: D ) -> Result<Self, D::Error> {
+                    crate::encoding::index_tagged::deserialize( deserializer )
+                }
+            }
+        }
     }
 }
 
@@ -233,21 +622,145 @@ pub mod proto {
     }
 }
 
-macro_rules! impl_exchange_from {
-    ( $($index:tt)+ ) => {
-        $(
-            impl<Enum,Variant> ExchangeFrom<Variant,[(); $index]> for Enum
-                where Enum: FromVariant<Variant,[(); $index]>
-            {
-                fn exchange_from( variant: Variant ) -> Self {
-                    Enum::from_variant( variant )
-                }
-            }
-        )+
-    };
+impl<Enum, Variant, const INDEX: usize> ExchangeFrom<Variant, [(); INDEX]> for Enum
+    where Enum: FromVariant<Variant, [(); INDEX]>
+{
+    fn exchange_from( variant: Variant ) -> Self {
+        Enum::from_variant( variant )
+    }
 }
 
-impl_exchange_from!( 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 );
+/// # Coproducts
+///
+/// An ad-hoc enum of unlimited variants is encoded as nested `Coprod`s, ended by `CNil`.
+/// For instance, `Coprod!(A,B,C)` is `Coprod<A, Coprod<B, Coprod<C, CNil>>>`.
+///
+/// `Enum!()` of more variants than the predefined enums, i.e. 16, or 32 with the feature
+/// "enum32", is lowered to `Coprod!()`.
+pub mod coproduct {
+    use crate::{EnumToEnum, ExchangeFrom, FromVariant, GetVariant, HasVariants};
+
+    /// The empty coproduct.
+    #[derive( Clone, Debug, PartialEq, Eq, PartialOrd, Ord )]
+    pub enum CNil {}
+
+    /// Either the head variant, or one of the variants in the tail coproduct.
+    #[derive( Clone, Debug, PartialEq, Eq, PartialOrd, Ord )]
+    pub enum Coprod<Head, Tail> {
+        Here( Head ),
+        There( Tail ),
+    }
+
+    /// Index of the head variant in a coproduct.
+    pub struct Here;
+
+    /// Index of the variant in the tail coproduct.
+    pub struct There<Index>( Index );
+
+    /// Indices of variants in a coproduct being exchanged from.
+    pub struct Nil;
+
+    /// Indices of variants in a coproduct being exchanged from.
+    pub struct Cons<Head, Tail>( Head, Tail );
+
+    impl<Head, Tail> FromVariant<Head, Here> for Coprod<Head, Tail> {
+        fn from_variant( variant: Head ) -> Self {
+            Coprod::Here( variant )
+        }
+    }
+
+    impl<Head, Tail, Variant, Index> FromVariant<Variant, There<Index>> for Coprod<Head, Tail>
+        where Tail: FromVariant<Variant, Index>
+    {
+        fn from_variant( variant: Variant ) -> Self {
+            Coprod::There( Tail::from_variant( variant ))
+        }
+    }
+
+    impl<Head, Tail> HasVariants for Coprod<Head, Tail> {}
+
+    impl<Head, Tail> GetVariant<Head, Here> for Coprod<Head, Tail> {
+        fn get_variant( &self ) -> Option<&Head> {
+            match self {
+                Coprod::Here( head ) => Some( head ),
+                Coprod::There( _ ) => None,
+            }
+        }
+
+        fn get_variant_mut( &mut self ) -> Option<&mut Head> {
+            match self {
+                Coprod::Here( head ) => Some( head ),
+                Coprod::There( _ ) => None,
+            }
+        }
+
+        fn take_variant( self ) -> Result<Head, Self> {
+            match self {
+                Coprod::Here( head ) => Ok( head ),
+                Coprod::There( tail ) => Err( Coprod::There( tail )),
+            }
+        }
+    }
+
+    impl<Head, Tail, Variant, Index> GetVariant<Variant, There<Index>> for Coprod<Head, Tail>
+        where Tail: GetVariant<Variant, Index>
+    {
+        fn get_variant( &self ) -> Option<&Variant> {
+            match self {
+                Coprod::Here( _ ) => None,
+                Coprod::There( tail ) => tail.get_variant(),
+            }
+        }
+
+        fn get_variant_mut( &mut self ) -> Option<&mut Variant> {
+            match self {
+                Coprod::Here( _ ) => None,
+                Coprod::There( tail ) => tail.get_variant_mut(),
+            }
+        }
+
+        fn take_variant( self ) -> Result<Variant, Self> {
+            match self {
+                Coprod::Here( head ) => Err( Coprod::Here( head )),
+                Coprod::There( tail ) => tail.take_variant().map_err( Coprod::There ),
+            }
+        }
+    }
+
+    impl<Enum, Variant> ExchangeFrom<Variant, Here> for Enum
+        where Enum: FromVariant<Variant, Here>
+    {
+        fn exchange_from( variant: Variant ) -> Self {
+            Enum::from_variant( variant )
+        }
+    }
+
+    impl<Enum, Variant, Index> ExchangeFrom<Variant, There<Index>> for Enum
+        where Enum: FromVariant<Variant, There<Index>>
+    {
+        fn exchange_from( variant: Variant ) -> Self {
+            Enum::from_variant( variant )
+        }
+    }
+
+    impl<Dest> ExchangeFrom<CNil, EnumToEnum<Nil>> for Dest {
+        fn exchange_from( src: CNil ) -> Self {
+            match src {}
+        }
+    }
+
+    impl<Head, Tail, Dest, HeadIndex, TailIndex> ExchangeFrom<Coprod<Head, Tail>, EnumToEnum<Cons<HeadIndex, TailIndex>>> for Dest
+        where Dest: ExchangeFrom<Head, HeadIndex>
+                  + ExchangeFrom<Tail, EnumToEnum<TailIndex>>
+    {
+        fn exchange_from( src: Coprod<Head, Tail> ) -> Self {
+            match src {
+                Coprod::Here(  head ) => Dest::exchange_from( head ),
+                Coprod::There( tail ) => Dest::exchange_from( tail ),
+            }
+        }
+    }
+}
 
 /// Wrapper for non-path types in type pattern matching using `#[ty_pat]` match
 /// ```rust,no_run
@@ -263,6 +776,96 @@ impl_exchange_from!( 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22
 /// ```
 pub type TyPat<T> = T;
 
+/// Helpers of `#[ty_pat]` which match an enum by value, or its variants by reference
+/// if the matched expression is of type `&Enum` or `&mut Enum`.
+///
+/// The mode is chosen by autoref-based method resolution on `&Src<S>`, where `S` is
+/// the type of the matched expression, and pins down the variant types of the ad-hoc
+/// enum via its `Adhoc::Mode`.
+#[doc( hidden )]
+pub mod ty_pat {
+    use crate::{AsMutProto, AsProto, ExchangeFrom};
+    use core::marker::PhantomData;
+
+    /// Maps a variant type of the ad-hoc enum to the one being matched in the mode.
+    pub trait Mode<'a, T: 'a> {
+        type Variant;
+    }
+
+    /// Keeps a variant type of the ad-hoc enum in every mode, e.g. `TyPat::<&T>`.
+    pub trait Keep<'a, T> {
+        type Variant;
+    }
+
+    impl<'a, T, M> Keep<'a, T> for M {
+        type Variant = T;
+    }
+
+    /// The ad-hoc enum generated by `#[ty_pat]`.
+    pub trait Adhoc {
+        type Mode;
+    }
+
+    pub struct ByValue;
+    pub struct ByRef;
+    pub struct ByMut;
+
+    impl<'a, T: 'a> Mode<'a, T> for ByValue { type Variant = T; }
+    impl<'a, T: 'a> Mode<'a, T> for ByRef   { type Variant = &'a T; }
+    impl<'a, T: 'a> Mode<'a, T> for ByMut   { type Variant = &'a mut T; }
+
+    pub struct Src<S>( PhantomData<S> );
+
+    /// Calls `f` with the matched value and the `Src` to choose the mode on.
+    pub fn with<S, R>( src: S, f: impl FnOnce( S, &Src<S> ) -> R ) -> R {
+        f( src, &Src( PhantomData ))
+    }
+
+    pub trait ByMutKind {
+        fn ty_pat_mode( &self ) -> ByMut { ByMut }
+    }
+
+    impl<'a, E: AsMutProto<'a>> ByMutKind for Src<&'a mut E> {}
+
+    pub trait ByRefKind {
+        fn ty_pat_mode( &self ) -> ByRef { ByRef }
+    }
+
+    impl<'a, E: AsProto<'a>> ByRefKind for Src<&'a E> {}
+
+    pub trait ByValueKind {
+        fn ty_pat_mode( &self ) -> ByValue { ByValue }
+    }
+
+    impl<S> ByValueKind for &Src<S> {}
+
+    impl ByValue {
+        pub fn exchange<S, Dest, Index>( self, src: S ) -> Dest
+            where Dest: ExchangeFrom<S, Index> + Adhoc<Mode=ByValue>
+        {
+            Dest::exchange_from( src )
+        }
+    }
+
+    impl ByRef {
+        pub fn exchange<'a, E, Dest, Index>( self, src: &'a E ) -> Dest
+            where E    : AsProto<'a>
+                , Dest : ExchangeFrom<E::Type, Index> + Adhoc<Mode=ByRef>
+        {
+            Dest::exchange_from( src.as_proto() )
+        }
+    }
+
+    impl ByMut {
+        pub fn exchange<'a, E, Dest, Index>( self, src: &'a mut E ) -> Dest
+            where E    : AsMutProto<'a>
+                , Dest : ExchangeFrom<E::Type, Index> + Adhoc<Mode=ByMut>
+        {
+            Dest::exchange_from( src.as_mut_proto() )
+        }
+    }
+}
+
 #[cfg( test )]
 mod tests {
     mod test_unnamed {
@@ -332,6 +935,173 @@ mod tests {
             let enum3: Enum3<i32, String, bool> = enum3.exchange_into();
             assert_eq!( enum3, Enum3::_0( 2018 ));
         }
+
+        #[test]
+        fn test_narrow() {
+            let enum3 = Enum3::<i32, String, bool>::exchange_from( 2018 );
+            let narrowed: Result<String, _> = enum3.narrow();
+            let enum2 = Enum2::<i32, bool>::exchange_from( narrowed.unwrap_err() );
+            assert_eq!( enum2, Enum2::_0( 2018 ));
+
+            let narrowed: Result<i32, Enum1<bool>> = enum2.narrow_into();
+            assert_eq!( narrowed, Ok( 2018 ));
+
+            let enum1 = Enum1::<bool>::exchange_from( true );
+            let narrowed: Result<bool, Enum0> = enum1.narrow_into();
+            assert_eq!( narrowed, Ok( true ));
+        }
+
+        #[test]
+        fn test_as_variants() {
+            let mut enum2 = Enum2::<String, i32>::exchange_from( 2018 );
+            assert_eq!( enum2.as_variants_ref(), Enum2::_1( &2018 ));
+
+            if let Enum2::_1( i ) = enum2.as_variants_mut() {
+                *i += 1;
+            }
+            let enum3: Enum3<bool, &i32, &String> = enum2.as_variants_ref().exchange_into();
+            assert_eq!( enum3, Enum3::_1( &2019 ));
+        }
+
+        #[test]
+        fn test_by_type() {
+            let mut enum3 = Enum3::<String, i32, bool>::exchange_from( 2018 );
+            assert!( enum3.is_variant::<i32>() );
+            assert!( !enum3.is_variant::<String>() );
+            assert_eq!( enum3.get_by_type::<i32>(), Some( &2018 ));
+            assert_eq!( enum3.get_by_type::<bool>(), None );
+
+            *enum3.get_by_type_mut::<i32>().unwrap() += 1;
+            assert_eq!( enum3, Enum3::_1( 2019 ));
+            assert_eq!( enum3.get_by_type_mut::<String>(), None );
+
+            let enum3 = enum3.take_by_type::<bool>().unwrap_err();
+            assert_eq!( enum3.take_by_type::<i32>(), Ok( 2019 ));
+        }
+
+        #[test]
+        fn test_map_variant() {
+            let enum3 = Enum3::<String, i32, bool>::exchange_from( 2018 );
+            let enum3: Enum3<String, u64, bool> = enum3.map_variant::<i32,u64>( |i| i as u64 + 1 );
+            assert_eq!( enum3, Enum3::_1( 2019 ));
+
+            let enum3 = enum3.map_variant::<String,usize>( |s| s.len() );
+            assert_eq!( enum3, Enum3::<usize, u64, bool>::_1( 2019 ));
+        }
+
+        #[test]
+        fn test_flatten() {
+            let nested = Enum2::<i32, Enum2<String, Enum2<bool, char>>>::exchange_from(
+                Enum2::<String, Enum2<bool, char>>::exchange_from( Enum2::<bool, char>::exchange_from( 'x' )));
+            let flat: Flat!( Enum2<i32, Enum2<String, Enum2<bool, char>>> ) = nested.flatten();
+            assert_eq!( flat, Enum4::_3( 'x' ));
+
+            let nested = Enum2::<i32, Enum3<String, bool, char>>::exchange_from( 2018 );
+            let flat: Flat!( i32, Enum!( String, bool, char )) = nested.flatten();
+            assert_eq!( flat, Enum4::_0( 2018 ));
+        }
+
+        #[test]
+        fn test_union_minus() {
+            let union: Union!( Enum!( i32, String ), Enum2<String, bool>, char ) = 'x'.exchange_into();
+            assert_eq!( union, Enum4::<i32, String, bool, char>::_3( 'x' ));
+
+            let minus: Minus!( Enum!( i32, String, bool ), String ) = true.exchange_into();
+            assert_eq!( minus, Enum2::<i32, bool>::_1( true ));
+
+            let minus: Minus!( Enum4<i32, String, bool, char>, Enum!( i32, char )) = true.exchange_into();
+            assert_eq!( minus, Enum2::<String, bool>::_1( true ));
+        }
+
+        #[test]
+        fn test_try_exchange_from() {
+            let enum4 = Enum4::<i32, String, bool, char>::exchange_from( 'x' );
+            let enum2 = Enum2::<char, String>::try_exchange_from( enum4 ).ok().unwrap();
+            assert_eq!( enum2, Enum2::_0( 'x' ));
+
+            let enum4 = Enum4::<i32, String, bool, char>::exchange_from( true );
+            let rest = Enum2::<char, String>::try_exchange_from( enum4 ).err().unwrap();
+            let rest: Enum2<i32, bool> = rest.exchange_into();
+            assert_eq!( rest, Enum2::_1( true ));
+        }
+
+        #[test]
+        fn test_try_exchange_into() {
+            let enum3 = Enum3::<i32, String, bool>::exchange_from( 2018 );
+            let result: Result<Enum1<i32>, _> = enum3.try_exchange_into();
+            assert_eq!( result.ok(), Some( Enum1::_0( 2018 )));
+
+            let enum3 = Enum3::<i32, String, bool>::exchange_from( 2018 );
+            let result: Result<Enum0, _> = enum3.clone().try_exchange_into();
+            assert_eq!( result.err(), Some( enum3 ));
+        }
+
+        #[test]
+        fn test_iterator() {
+            let iter = Enum2::<std::ops::Range<u32>, std::vec::IntoIter<u32>>::exchange_from( 0..4 );
+            assert_eq!( iter.size_hint(), (4, Some(4)) );
+            assert_eq!( iter.len(), 4 );
+            assert_eq!( iter.clone().fold( 1, |acc, i| acc*(i+1) ), 24 );
+            assert_eq!( iter.clone().rfold( vec![], |mut acc, i| { acc.push(i); acc }), vec![ 3, 2, 1, 0 ]);
+            assert_eq!( iter.clone().nth(2), Some(2) );
+            assert_eq!( iter.clone().nth_back(2), Some(1) );
+            assert_eq!( iter.clone().last(), Some(3) );
+            assert_eq!( iter.clone().count(), 4 );
+            assert_eq!( iter.clone().max(), Some(3) );
+            assert_eq!( iter.clone().sum::<u32>(), 6 );
+            assert_eq!( iter.clone().position( |i| i == 2 ), Some(2) );
+
+            let iter = Enum2::<std::ops::Range<u32>, std::vec::IntoIter<u32>>::exchange_from( vec![ 5, 6 ].into_iter() );
+            assert_eq!( iter.size_hint(), (2, Some(2)) );
+            assert_eq!( iter.collect::<Vec<_>>(), vec![ 5, 6 ]);
+        }
+
+        #[test]
+        fn test_more_traits() {
+            use std::collections::hash_map::DefaultHasher;
+            use std::fmt::Write;
+            use std::hash::{Hash, Hasher};
+
+            fn hash_of<T: Hash>( value: T ) -> u64 {
+                let mut hasher = DefaultHasher::new();
+                value.hash( &mut hasher );
+                hasher.finish()
+            }
+            assert_eq!( hash_of( Enum2::<i32, String>::_1( "rust".to_string() )), hash_of( "rust".to_string() ));
+
+            let mut hasher = Enum1::<DefaultHasher>::_0( DefaultHasher::new() );
+            hasher.write_u32( 2018 );
+            assert_eq!( hasher.finish(), { let mut h = DefaultHasher::new(); h.write_u32( 2018 ); h.finish() });
+
+            let mut text = Enum1::<String>::_0( String::new() );
+            let year = 2018;
+            write!( text, "{}-{}", year, year+1 ).unwrap();
+            assert_eq!( text, Enum1::_0( "2018-2019".to_string() ));
+
+            let number = Enum2::<u8, u32>::_1( 255 );
+            assert_eq!( format!( "{:x} {:X} {:o} {:b}", number, number, number, number ), "ff FF 377 11111111" );
+
+            let bytes = Enum2::<Vec<u8>, [u8; 2]>::_1( [ 4, 2 ]);
+            let slice: &[u8] = bytes.as_ref();
+            assert_eq!( slice, &[ 4, 2 ]);
+        }
+
+        struct Describe;
+
+        impl<T: std::fmt::Debug> EnumVisitor<T> for Describe {
+            type Output = String;
+            fn visit( self, variant: T ) -> String { format!( "{:?}", variant )}
+        }
+
+        #[test]
+        fn test_accept() {
+            let enum3 = Enum3::<i32, String, bool>::exchange_from( true );
+            assert_eq!( enum3.as_proto().accept( Describe ), "true" );
+            assert_eq!( enum3.accept( Describe ), "true" );
+
+            let enum2 = Enum2::<i32, String>::exchange_from( "rust".to_string() );
+            assert_eq!( enum2.as_variants_ref().accept( Describe ), "\"rust\"" );
+        }
     }
 
     mod test_named {
@@ -450,5 +1220,212 @@ mod tests {
             let three = Three::<bool, String, i32>::exchange_from( enum3 );
             assert_eq!( three, Three::Third( 2018 ));
         }
+
+        #[test]
+        fn test_narrow_named() {
+            let three = Three::<bool, String, i32>::exchange_from( "rust".to_string() );
+            let narrowed: Result<i32, Two<String, bool>> = three.clone().narrow_into();
+            assert_eq!( narrowed, Err( Two::Former( "rust".to_string() )));
+
+            let narrowed: Result<String, Enum2<bool, i32>> = three.narrow_into();
+            assert_eq!( narrowed, Ok( "rust".to_string() ));
+        }
+
+        #[test]
+        fn test_by_type_named() {
+            let mut three = Three::<bool, String, i32>::exchange_from( "rust".to_string() );
+            assert!( three.is_variant::<String>() );
+            assert!( !three.is_variant::<bool>() );
+
+            three.get_by_type_mut::<String>().unwrap().push_str( "acean" );
+            assert_eq!( three.get_by_type::<String>().map( String::as_str ), Some( "rustacean" ));
+
+            let three = three.take_by_type::<i32>().unwrap_err();
+            assert_eq!( three.take_by_type::<String>(), Ok( "rustacean".to_string() ));
+        }
+
+        #[derive( Exchange, Debug, PartialEq )]
+        enum Value {
+            Bin( Vec<u8> ),
+            Text( String ),
+        }
+
+        #[test]
+        fn test_map_variant_named() {
+            let three = Three::<bool, String, i32>::exchange_from( "rust".to_string() );
+            let three: Three<bool, usize, i32> = three.map_variant::<String,usize>( |s| s.len() );
+            assert_eq!( three, Three::Second( 4 ));
+
+            let value = Value::Text( "rust".to_string() );
+            let value = value.map_variant::<Vec<u8>,usize>( |bin| bin.len() );
+            let value: Enum2<usize, String> = value.exchange_into();
+            assert_eq!( value, Enum2::_1( "rust".to_string() ));
+        }
+
+        #[test]
+        fn test_try_exchange_named() {
+            let three = Three::<bool, String, i32>::exchange_from( 2018 );
+            let two = Two::<i32, bool>::try_exchange_from( three ).ok().unwrap();
+            assert_eq!( two, Two::Former( 2018 ));
+
+            let three = Three::<bool, String, i32>::exchange_from( "rust".to_string() );
+            let rest = Two::<i32, bool>::try_exchange_from( three ).err().unwrap();
+            let one: One<String> = rest.exchange_into();
+            assert_eq!( one, One::The( "rust".to_string() ));
+        }
+
+        struct Size;
+
+        impl<T> EnumVisitor<T> for Size {
+            type Output = usize;
+            fn visit( self, _: T ) -> usize { std::mem::size_of::<T>() }
+        }
+
+        #[test]
+        fn test_accept_named() {
+            let three = Three::<u8, u16, u32>::exchange_from( 2018_u16 );
+            assert_eq!( three.accept( Size ), 2 );
+
+            let two = Two::<u8, String>::exchange_from( 42_u8 );
+            assert_eq!( two.as_proto().accept( Size ), std::mem::size_of::<&u8>() );
+        }
+
+        #[derive( Exchange, Clone, Debug, PartialEq )]
+        enum Shapes {
+            Timeout,
+            Io{ path: String, code: i32 },
+            Pair( u8, bool ),
+            Text( String ),
+        }
+
+        #[test]
+        fn test_non_newtype_variants() {
+            assert_eq!( Shapes::from_variant( () ), Shapes::Timeout );
+            assert_eq!( Shapes::from_variant(( "/tmp".to_owned(), 2 )), Shapes::Io{ path: "/tmp".to_owned(), code: 2 });
+            assert_eq!( Shapes::from_variant(( 7_u8, true )), Shapes::Pair( 7, true ));
+
+            let enum4 = Enum4::<(), (String,i32), (u8,bool), String>::exchange_from( Shapes::Pair( 7, true ));
+            assert_eq!( enum4, Enum4::_2(( 7, true )));
+            assert_eq!( Shapes::exchange_from( enum4 ), Shapes::Pair( 7, true ));
+
+            let shapes: Shapes = Enum2::<(u8,bool), ()>::_1( () ).exchange_into();
+            assert_eq!( shapes, Shapes::Timeout );
+
+            let io = Shapes::Io{ path: "/tmp".to_owned(), code: 2 };
+            match io.as_proto() {
+                crate::proto::__4::_1(( path, code )) => assert_eq!(( path.as_str(), *code ), ( "/tmp", 2 )),
+                _ => unreachable!(),
+            }
+            if let crate::proto::__4::_0( unit ) = Shapes::Timeout.as_proto() {
+                assert_eq!( unit, &() );
+            } else {
+                unreachable!();
+            }
+
+            let mut pair = Shapes::Pair( 7, true );
+            if let crate::proto::__4::_2(( n, flag )) = pair.as_mut_proto() {
+                *n += 1;
+                *flag = false;
+            }
+            assert_eq!( pair, Shapes::Pair( 8, false ));
+            let mut text = Shapes::Text( "text".to_owned() );
+            if let crate::proto::__4::_3( text ) = text.as_mut_proto() {
+                text.push( '!' );
+            }
+            assert_eq!( text, Shapes::Text( "text!".to_owned() ));
+
+            let narrowed: Result<(String,i32), _> = io.clone().narrow();
+            assert_eq!( narrowed.ok(), Some(( "/tmp".to_owned(), 2 )));
+            let narrowed: Result<(), _> = io.clone().narrow();
+            match narrowed {
+                Err( crate::proto::__3::_0(( path, code ))) => assert_eq!(( path.as_str(), code ), ( "/tmp", 2 )),
+                _ => unreachable!(),
+            }
+
+            assert_eq!( Shapes::Text( "text".to_owned() ).get_variant(), Some( &"text".to_owned() ));
+            match io.clone().map_variant( |(path, code): (String,i32)| path.len() as i32 + code ) {
+                crate::proto::__4::_1( mapped ) => assert_eq!( mapped, 6 ),
+                _ => unreachable!(),
+            }
+            assert_eq!( io.accept( Size ), std::mem::size_of::<(String,i32)>() );
+        }
+
+        #[derive( Exchange, Debug, PartialEq )]
+        enum Messages {
+            Warning( String ),
+            Error( String ),
+        }
+
+        #[derive( Exchange, Debug, PartialEq )]
+        enum Signal {
+            Start,
+            Stop,
+        }
+
+        #[test]
+        fn test_variants_of_same_type() {
+            assert_eq!( Messages::Warning( "low disk".to_owned() ).accept( Size ), std::mem::size_of::<String>() );
+            assert_eq!( <Messages as FromVariant<String, [(); 1]>>::from_variant( "oops".to_owned() ), Messages::Error( "oops".to_owned() ));
+            assert_eq!( Signal::Stop.accept( Size ), 0 );
+            assert_eq!( <Signal as FromVariant<(), [(); 0]>>::from_variant( () ), Signal::Start );
+        }
+
+        struct Missing;
+        struct Forbidden;
+
+        #[derive( Exchange, Clone, Debug, PartialEq )]
+        enum FsError {
+            #[exchange( tag = Missing )]
+            NotFound( String ),
+            #[exchange( tag = Forbidden )]
+            Denied( String ),
+            Code( i32 ),
+        }
+
+        #[derive( Exchange, Debug, PartialEq )]
+        enum AppError {
+            #[exchange( tag = Forbidden )]
+            Denied( String ),
+            Code( i32 ),
+            #[exchange( tag = Missing )]
+            NotFound( String ),
+            #[exchange( skip )]
+            Internal,
+        }
+
+        #[test]
+        fn test_tagged_variants() {
+            let not_found = FsError::from_variant( Tagged::<Missing,_>::new( "a.txt".to_owned() ));
+            assert_eq!( not_found, FsError::NotFound( "a.txt".to_owned() ));
+            let denied = FsError::from_variant( Tagged::<Forbidden,_>::new( "b.txt".to_owned() ));
+            assert_eq!( denied, FsError::Denied( "b.txt".to_owned() ));
+
+            assert_eq!( AppError::exchange_from( not_found.clone() ), AppError::NotFound( "a.txt".to_owned() ));
+            assert_eq!( AppError::exchange_from( denied.clone() ), AppError::Denied( "b.txt".to_owned() ));
+            assert_eq!( AppError::exchange_from( FsError::Code( 2 )), AppError::Code( 2 ));
+
+            let narrowed: Result<Tagged<Forbidden,String>, _> = denied.narrow();
+            assert_eq!( narrowed.ok().map( Tagged::into_inner ), Some( "b.txt".to_owned() ));
+            let narrowed: Result<Tagged<Forbidden,String>, _> = not_found.clone().narrow();
+            assert!( narrowed.is_err() );
+
+            let mut renamed = not_found.clone();
+            if let crate::proto::__3::_0( name ) = renamed.as_mut_proto() {
+                name.value.push_str( ".bak" );
+            }
+            assert_eq!( renamed, FsError::NotFound( "a.txt.bak".to_owned() ));
+
+            assert_eq!( not_found.accept( Size ), std::mem::size_of::<String>() );
+            assert_eq!( FsError::NotFound( "a.txt".to_owned() ).get_variant().map( |v: &Tagged<Missing,String>| v.value.len() ), Some( 5 ));
+            assert_eq!( AppError::Code( 2 ).get_variant(), Some( &2 ));
+            assert_eq!( AppError::Internal.get_variant(), None::<&i32> );
+
+            let mut code = AppError::Code( 2 );
+            assert!( code.is_variant::<i32>() );
+            *code.get_by_type_mut::<i32>().unwrap() += 1;
+            assert_eq!( code.take_by_type::<i32>(), Ok( 3 ));
+            assert!( !AppError::Internal.is_variant::<i32>() );
+            assert_eq!( AppError::Internal.take_by_type::<i32>(), Err( AppError::Internal ));
+        }
     }
 }
@@ -63,6 +63,8 @@
 ///
 /// `AsMut`
 ///
+/// `AsRef<[T]>`, `AsMut<[T]>` for slices, e.g. `_impl!(T) AsRef<[T]>`
+///
 /// `DoubleEndedIterator`
 ///
 /// `ExactSizeIterator`
@@ -79,6 +81,10 @@
 /// impl_trait!{ Iterator _for!( Type )}
 /// ```
 ///
+/// Besides `next()`, the provided methods that iterators usually specialize, e.g.
+/// `size_hint()`, `fold()` and `nth()`, are forwarded to the variants too. The
+/// methods involving the unstable `Try` trait, e.g. `try_fold()`, are not.
+///
 /// The example of implementing `Fn`:
 ///
 /// ```text
@@ -87,12 +93,34 @@
 ///
 /// ## Traits with full path
 ///
+/// `std::borrow::Borrow`
+///
+/// `std::borrow::BorrowMut`
+///
 /// `std::error::Error`
 ///
+/// `std::fmt::Binary`
+///
 /// `std::fmt::Debug`
 ///
 /// `std::fmt::Display`
 ///
+/// `std::fmt::LowerHex`
+///
+/// `std::fmt::Octal`
+///
+/// `std::fmt::Pointer`
+///
+/// `std::fmt::UpperHex`
+///
+/// `std::fmt::Write`
+///
+/// `std::future::Future`
+///
+/// `std::hash::Hash`
+///
+/// `std::hash::Hasher`
+///
 /// `std::iter::FusedIterator`
 ///
 /// `std::iter::TrustedLen`
@@ -117,12 +145,90 @@
 ///
 /// `std::ops::RangeBounds`
 ///
+/// The borrowed type of `Borrow`/`BorrowMut` should be a concrete one, e.g.
+/// `std::borrow::Borrow<str>`, because `Borrow<T>` has been implemented for `T`.
+///
+/// `Sum`/`Product` are not supported, since nothing tells which variant the items
+/// should accumulate into. Sum the items into the variant type then wrap it instead,
+/// e.g. `Enum2::_1( iter.sum::<u64>() )`.
+///
+/// The `std` prefix can be replaced with `core` for traits in core library, e.g.
+/// `core::fmt::Display`, which is required in `no_std` environments.
+///
+/// The traits above, except the `Write` ones, can also be named by the bare names,
+/// e.g. `Display`, once imported by `use enumx::export::*`. A bare name other than
+/// `Debug` and `Hash` resolves to the companion macro in scope, so an explicitly
+/// imported user-defined trait of the same name takes the place of the std one.
+///
 /// The example of implementing `std::ops::Generator`:
 ///
 /// ```text
 /// impl_trait!{ _impl!(R) std::ops::Generator<R> _for!( Type )}
 /// ```
 ///
+/// ## Traits in other crates
+///
+/// The crate which the trait belongs to should be a dependency of the crate
+/// invoking the macro. Predefined enums implement these traits if the crate feature
+/// "futures" or "tokio" is opted in.
+///
+/// `futures_core::Stream`
+///
+/// `futures_io::AsyncBufRead`
+///
+/// `futures_io::AsyncRead`
+///
+/// `futures_io::AsyncWrite`
+///
+/// `tokio::io::AsyncBufRead`
+///
+/// `tokio::io::AsyncRead`
+///
+/// `tokio::io::AsyncWrite`
+///
+/// The traits with poll methods, e.g. `std::future::Future`, are implemented by
+/// pin projection, which is sound because the pinned enum never moves its variant.
+///
+/// ## User-defined traits
+///
+/// Tag the trait definition with `#[enumx::delegate]`, which generates a companion
+/// macro of the same name as the trait. Then `impl_trait!{}` can implement it by
+/// the trait's path:
+///
+/// ```rust
+/// use enumx::export::*;
+/// use enumx::predefined::*;
+///
+/// #[enumx::delegate]
+/// trait Shape {
+///     fn area( &self ) -> f64;
+/// }
+///
+/// struct Square( f64 );
+/// impl Shape for Square { fn area( &self ) -> f64 { self.0 * self.0 }}
+///
+/// struct Circle( f64 );
+/// impl Shape for Circle { fn area( &self ) -> f64 { 3.0 * self.0 * self.0 }}
+///
+/// impl_trait!{ Shape _for!( Enum![2..=2] )}
+///
+/// assert_eq!( Enum2::<Square,Circle>::_1( Circle( 1.0 )).area(), 3.0 );
+/// ```
+///
+/// The methods with receivers are forwarded to the variants. Associated types
+/// should be the same in all variants. Associated consts without default values are
+/// rejected, since the variants may disagree on them, unless the trait is tagged with
+/// `#[enumx::delegate( first_variant_consts )]` to take them from the first variant.
+///
+/// The companion macro has the trait's visibility: a `pub` trait's companion macro is
+/// exported under a hidden name and re-exported along with the trait, so other crates
+/// can import both by the trait's path. The types in the trait's method signatures
+/// should be nameable where `impl_trait!{}` is invoked.
+///
+/// A trait named like one in std, e.g. `Read`, should be imported by name where
+/// `impl_trait!{}` or `#[sum]` uses it. In the module defining the trait, the name is
+/// ambiguous with the std companion macro from `use enumx::export::*`.
+///
 /// ## Unstable traits
 ///
 /// To implement these traits, the crate feature "unstable" should be opted in.
@@ -163,6 +269,29 @@
 /// ```
 #[macro_export]
 macro_rules! impl_trait {
+    ($(_impl!($($gen:tt),*))* core::$($rest:tt)+) => {
+        impl_trait!{ $(_impl!($($gen),*))* std::$($rest)+ }
+    };
+    ($(_impl!($($gen:tt),*))* AsRef<[$t:ident]> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> AsRef<[$t]> for $($ty)+
+                where _Variants!(): AsRef<[$t]>
+                      $($(, $pred)*)*
+            {
+                fn as_ref( &self ) -> &[$t] { _match!( _variant!().as_ref() )}
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* AsMut<[$t:ident]> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> AsMut<[$t]> for $($ty)+
+                where _Variants!(): AsMut<[$t]>
+                      $($(, $pred)*)*
+            {
+                fn as_mut( &mut self ) -> &mut [$t] { _match!( _variant!().as_mut() )}
+            }
+        }
+    };
     ($(_impl!($($gen:tt),*))* AsRef<$t:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
             impl<$($($gen,)*)*> AsRef<$t> for $($ty)+
@@ -203,6 +332,15 @@ macro_rules! impl_trait {
                 fn next_back( &mut self ) -> Option<_Item> {
                     _match!( _variant!().next_back() )
                 }
+                fn nth_back( &mut self, n: usize ) -> Option<_Item> {
+                    _match!( _variant!().nth_back( n ))
+                }
+                fn rfold<B,F>( self, init: B, f: F ) -> B where F: FnMut( B, _Item ) -> B {
+                    _match!( _variant!().rfold( init, f ))
+                }
+                fn rfind<P>( &mut self, predicate: P ) -> Option<_Item> where P: FnMut( &_Item ) -> bool {
+                    _match!( _variant!().rfind( predicate ))
+                }
             }
         }
     };
@@ -213,6 +351,7 @@ macro_rules! impl_trait {
                     , Self         : Iterator<Item=_Item>
                       $($(, $pred)*)*
             {
+                fn len( &self ) -> usize { _match!( _variant!().len() )}
             }
         }
     };
@@ -275,6 +414,79 @@ macro_rules! impl_trait {
             {
                 type Item = _Item;
                 fn next( &mut self ) -> Option<_Item> { _match!( _variant!().next() )}
+                fn size_hint( &self ) -> (usize, Option<usize>) { _match!( _variant!().size_hint() )}
+                fn count( self ) -> usize { _match!( _variant!().count() )}
+                fn last( self ) -> Option<_Item> { _match!( _variant!().last() )}
+                fn nth( &mut self, n: usize ) -> Option<_Item> { _match!( _variant!().nth( n ))}
+                fn fold<B,F>( self, init: B, f: F ) -> B where F: FnMut( B, _Item ) -> B {
+                    _match!( _variant!().fold( init, f ))
+                }
+                fn for_each<F>( self, f: F ) where F: FnMut( _Item ) {
+                    _match!( _variant!().for_each( f ))
+                }
+                fn collect<B>( self ) -> B where B: core::iter::FromIterator<_Item> {
+                    _match!( _variant!().collect() )
+                }
+                fn all<F>( &mut self, f: F ) -> bool where F: FnMut( _Item ) -> bool {
+                    _match!( _variant!().all( f ))
+                }
+                fn any<F>( &mut self, f: F ) -> bool where F: FnMut( _Item ) -> bool {
+                    _match!( _variant!().any( f ))
+                }
+                fn find<P>( &mut self, predicate: P ) -> Option<_Item> where P: FnMut( &_Item ) -> bool {
+                    _match!( _variant!().find( predicate ))
+                }
+                fn find_map<B,F>( &mut self, f: F ) -> Option<B> where F: FnMut( _Item ) -> Option<B> {
+                    _match!( _variant!().find_map( f ))
+                }
+                fn position<P>( &mut self, predicate: P ) -> Option<usize> where P: FnMut( _Item ) -> bool {
+                    _match!( _variant!().position( predicate ))
+                }
+                fn max( self ) -> Option<_Item> where _Item: Ord {
+                    _match!( _variant!().max() )
+                }
+                fn min( self ) -> Option<_Item> where _Item: Ord {
+                    _match!( _variant!().min() )
+                }
+                fn max_by_key<B,F>( self, f: F ) -> Option<_Item> where B: Ord, F: FnMut( &_Item ) -> B {
+                    _match!( _variant!().max_by_key( f ))
+                }
+                fn min_by_key<B,F>( self, f: F ) -> Option<_Item> where B: Ord, F: FnMut( &_Item ) -> B {
+                    _match!( _variant!().min_by_key( f ))
+                }
+                fn max_by<F>( self, compare: F ) -> Option<_Item> where F: FnMut( &_Item, &_Item ) -> core::cmp::Ordering {
+                    _match!( _variant!().max_by( compare ))
+                }
+                fn min_by<F>( self, compare: F ) -> Option<_Item> where F: FnMut( &_Item, &_Item ) -> core::cmp::Ordering {
+                    _match!( _variant!().min_by( compare ))
+                }
+                fn sum<S>( self ) -> S where S: core::iter::Sum<_Item> {
+                    _match!( _variant!().sum() )
+                }
+                fn product<P>( self ) -> P where P: core::iter::Product<_Item> {
+                    _match!( _variant!().product() )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::borrow::Borrow<$t:ty> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::borrow::Borrow<$t> for $($ty)+
+                where _Variants!(): core::borrow::Borrow<$t>
+                      $($(, $pred)*)*
+            {
+                fn borrow( &self ) -> &$t { _match!( _variant!().borrow() )}
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::borrow::BorrowMut<$t:ty> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::borrow::BorrowMut<$t> for $($ty)+
+                where _Variants!() : core::borrow::BorrowMut<$t>
+                    ,         Self : core::borrow::Borrow<$t>
+                      $($(, $pred)*)*
+            {
+                fn borrow_mut( &mut self ) -> &mut $t { _match!( _variant!().borrow_mut() )}
             }
         }
     };
@@ -282,8 +494,8 @@ macro_rules! impl_trait {
         def_impls! {
             impl<$($($gen,)*)*> std::error::Error for $($ty)+
                 where _Variants!() : std::error::Error
-                    ,         Self : std::fmt::Debug
-                                   + std::fmt::Display
+                    ,         Self : core::fmt::Debug
+                                   + core::fmt::Display
                       $($(, $pred)*)*
             {
                fn source( &self ) -> Option<&(dyn std::error::Error + 'static)> { _match!( _variant!().source() )}
@@ -292,11 +504,11 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::fmt::Debug _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)*> std::fmt::Debug for $($ty)+
-                where _Variants!() : std::fmt::Debug
+            impl<$($($gen,)*)*> core::fmt::Debug for $($ty)+
+                where _Variants!() : core::fmt::Debug
                       $($(, $pred)*)*
             {
-                fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
                     _match!( _variant!().fmt(f) )
                 }
             }
@@ -304,20 +516,150 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::fmt::Display _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)*> std::fmt::Display for $($ty)+
-                where _Variants!(): std::fmt::Display
+            impl<$($($gen,)*)*> core::fmt::Display for $($ty)+
+                where _Variants!(): core::fmt::Display
                       $($(, $pred)*)*
             {
-                fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
                     _match!( _variant!().fmt(f) )
                 }
             }
         }
     };
+    ($(_impl!($($gen:tt),*))* std::fmt::Binary _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::Binary for $($ty)+
+                where _Variants!(): core::fmt::Binary
+                      $($(, $pred)*)*
+            {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
+                    _match!( _variant!().fmt(f) )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::fmt::LowerHex _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::LowerHex for $($ty)+
+                where _Variants!(): core::fmt::LowerHex
+                      $($(, $pred)*)*
+            {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
+                    _match!( _variant!().fmt(f) )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::fmt::Octal _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::Octal for $($ty)+
+                where _Variants!(): core::fmt::Octal
+                      $($(, $pred)*)*
+            {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
+                    _match!( _variant!().fmt(f) )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::fmt::Pointer _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::Pointer for $($ty)+
+                where _Variants!(): core::fmt::Pointer
+                      $($(, $pred)*)*
+            {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
+                    _match!( _variant!().fmt(f) )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::fmt::UpperHex _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::UpperHex for $($ty)+
+                where _Variants!(): core::fmt::UpperHex
+                      $($(, $pred)*)*
+            {
+                fn fmt( &self, f: &mut core::fmt::Formatter ) -> core::fmt::Result {
+                    _match!( _variant!().fmt(f) )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::fmt::Write _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::fmt::Write for $($ty)+
+                where _Variants!(): core::fmt::Write
+                      $($(, $pred)*)*
+            {
+                fn write_str( &mut self, s: &str ) -> core::fmt::Result {
+                    _match!( _variant!().write_str( s ))
+                }
+
+                fn write_char( &mut self, c: char ) -> core::fmt::Result {
+                    _match!( _variant!().write_char( c ))
+                }
+
+                fn write_fmt( &mut self, args: core::fmt::Arguments<'_> ) -> core::fmt::Result {
+                    _match!( _variant!().write_fmt( args ))
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::future::Future _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)* _Output> core::future::Future for $($ty)+
+                where _Variants!(): core::future::Future<Output=_Output>
+                      $($(, $pred)*)*
+            {
+                type Output = _Output;
+                fn poll( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<_Output> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll( cx )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::hash::Hash _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::hash::Hash for $($ty)+
+                where _Variants!(): core::hash::Hash
+                      $($(, $pred)*)*
+            {
+                fn hash<_H: core::hash::Hasher>( &self, state: &mut _H ) {
+                    _match!( _variant!().hash( state ))
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* std::hash::Hasher _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> core::hash::Hasher for $($ty)+
+                where _Variants!(): core::hash::Hasher
+                      $($(, $pred)*)*
+            {
+                fn finish( &self ) -> u64 { _match!( _variant!().finish() )}
+                fn write( &mut self, bytes: &[u8] ) { _match!( _variant!().write( bytes ))}
+                fn write_u8( &mut self, i: u8 ) { _match!( _variant!().write_u8( i ))}
+                fn write_u16( &mut self, i: u16 ) { _match!( _variant!().write_u16( i ))}
+                fn write_u32( &mut self, i: u32 ) { _match!( _variant!().write_u32( i ))}
+                fn write_u64( &mut self, i: u64 ) { _match!( _variant!().write_u64( i ))}
+                fn write_u128( &mut self, i: u128 ) { _match!( _variant!().write_u128( i ))}
+                fn write_usize( &mut self, i: usize ) { _match!( _variant!().write_usize( i ))}
+                fn write_i8( &mut self, i: i8 ) { _match!( _variant!().write_i8( i ))}
+                fn write_i16( &mut self, i: i16 ) { _match!( _variant!().write_i16( i ))}
+                fn write_i32( &mut self, i: i32 ) { _match!( _variant!().write_i32( i ))}
+                fn write_i64( &mut self, i: i64 ) { _match!( _variant!().write_i64( i ))}
+                fn write_i128( &mut self, i: i128 ) { _match!( _variant!().write_i128( i ))}
+                fn write_isize( &mut self, i: isize ) { _match!( _variant!().write_isize( i ))}
+            }
+        }
+    };
     ($(_impl!($($gen:tt),*))* std::iter::FusedIterator _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Item> std::iter::FusedIterator for $($ty)+
-                where _Variants!() : std::iter::FusedIterator<Item=_Item>
+            impl<$($($gen,)*)* _Item> core::iter::FusedIterator for $($ty)+
+                where _Variants!() : core::iter::FusedIterator<Item=_Item>
                     , Self         : Iterator<Item=_Item>
                       $($(, $pred)*)*
             {
@@ -326,8 +668,8 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::iter::TrustedLen _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            unsafe impl<$($($gen,)*)* _Item> std::iter::TrustedLen for $($ty)+
-                where _Variants!() : std::iter::TrustedLen<Item=_Item>
+            unsafe impl<$($($gen,)*)* _Item> core::iter::TrustedLen for $($ty)+
+                where _Variants!() : core::iter::TrustedLen<Item=_Item>
                     , Self         : Iterator<Item=_Item>
                       $($(, $pred)*)*
             {
@@ -348,6 +690,14 @@ macro_rules! impl_trait {
                 fn consume( &mut self, amt: usize ) {
                     _match!( _variant!().consume( amt ))
                 }
+
+                fn read_until( &mut self, byte: u8, buf: &mut Vec<u8> ) -> std::io::Result<usize> {
+                    _match!( _variant!().read_until( byte, buf ))
+                }
+
+                fn read_line( &mut self, buf: &mut String ) -> std::io::Result<usize> {
+                    _match!( _variant!().read_line( buf ))
+                }
             }
         }
     };
@@ -360,6 +710,22 @@ macro_rules! impl_trait {
                 fn read( &mut self, buf: &mut [u8] ) -> std::io::Result<usize> {
                     _match!( _variant!().read( buf ))
                 }
+
+                fn read_vectored( &mut self, bufs: &mut [std::io::IoSliceMut<'_>] ) -> std::io::Result<usize> {
+                    _match!( _variant!().read_vectored( bufs ))
+                }
+
+                fn read_to_end( &mut self, buf: &mut Vec<u8> ) -> std::io::Result<usize> {
+                    _match!( _variant!().read_to_end( buf ))
+                }
+
+                fn read_to_string( &mut self, buf: &mut String ) -> std::io::Result<usize> {
+                    _match!( _variant!().read_to_string( buf ))
+                }
+
+                fn read_exact( &mut self, buf: &mut [u8] ) -> std::io::Result<()> {
+                    _match!( _variant!().read_exact( buf ))
+                }
             }
         }
     };
@@ -372,6 +738,14 @@ macro_rules! impl_trait {
                 fn seek( &mut self, pos: std::io::SeekFrom ) -> std::io::Result<u64> {
                     _match!( _variant!().seek( pos ))
                 }
+
+                fn rewind( &mut self ) -> std::io::Result<()> {
+                    _match!( _variant!().rewind() )
+                }
+
+                fn stream_position( &mut self ) -> std::io::Result<u64> {
+                    _match!( _variant!().stream_position() )
+                }
             }
         }
     };
@@ -388,13 +762,25 @@ macro_rules! impl_trait {
                 fn flush( &mut self ) -> std::io::Result<()> {
                     _match!( _variant!().flush() )
                 }
+
+                fn write_vectored( &mut self, bufs: &[std::io::IoSlice<'_>] ) -> std::io::Result<usize> {
+                    _match!( _variant!().write_vectored( bufs ))
+                }
+
+                fn write_all( &mut self, buf: &[u8] ) -> std::io::Result<()> {
+                    _match!( _variant!().write_all( buf ))
+                }
+
+                fn write_fmt( &mut self, fmt: std::fmt::Arguments<'_> ) -> std::io::Result<()> {
+                    _match!( _variant!().write_fmt( fmt ))
+                }
             }
         }
     };
     ($(_impl!($($gen:tt),*))* std::ops::Deref _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Target> std::ops::Deref for $($ty)+
-                where _Variants!(): std::ops::Deref<Target=_Target>
+            impl<$($($gen,)*)* _Target> core::ops::Deref for $($ty)+
+                where _Variants!(): core::ops::Deref<Target=_Target>
                       $($(, $pred)*)*
             {
                 type Target = _Target;
@@ -404,9 +790,9 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::ops::DerefMut _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Target> std::ops::DerefMut for $($ty)+
-                where _Variants!() : std::ops::DerefMut<Target=_Target>
-                    ,         Self : std::ops::Deref<Target=_Target>
+            impl<$($($gen,)*)* _Target> core::ops::DerefMut for $($ty)+
+                where _Variants!() : core::ops::DerefMut<Target=_Target>
+                    ,         Self : core::ops::Deref<Target=_Target>
                       $($(, $pred)*)*
             {
                 fn deref_mut( &mut self ) -> &mut _Target { _match!( _variant!().deref_mut() )}
@@ -415,15 +801,15 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::ops::Generator<$r:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Yield, _Return> std::ops::Generator<$r> for $($ty)+
-                where _Variants!(): std::ops::Generator<$r,Yield=_Yield,Return=_Return>
+            impl<$($($gen,)*)* _Yield, _Return> core::ops::Generator<$r> for $($ty)+
+                where _Variants!(): core::ops::Generator<$r,Yield=_Yield,Return=_Return>
                       $($(, $pred)*)*
             {
                 type Yield = _Yield;
                 type Return = _Return;
-                fn resume( self: std::pin::Pin<&mut Self>, arg: $r ) -> std::ops::GeneratorState<Self::Yield, Self::Return> {
+                fn resume( self: core::pin::Pin<&mut Self>, arg: $r ) -> core::ops::GeneratorState<Self::Yield, Self::Return> {
                     _match!( unsafe{ self.get_unchecked_mut() } =>
-                         unsafe{ std::pin::Pin::new_unchecked( _variant!() )}.resume( arg )
+                         unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.resume( arg )
                     )
                 }
             }
@@ -431,8 +817,8 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::ops::Index<$idx:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Output> std::ops::Index<$idx> for $($ty)+
-                where _Variants!(): std::ops::Index<$idx,Output=_Output>
+            impl<$($($gen,)*)* _Output> core::ops::Index<$idx> for $($ty)+
+                where _Variants!(): core::ops::Index<$idx,Output=_Output>
                       $($(, $pred)*)*
             {
                 type Output = _Output;
@@ -442,9 +828,9 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::ops::IndexMut<$idx:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)* _Output> std::ops::IndexMut<$idx> for $($ty)+
-                where _Variants!() : std::ops::IndexMut<$idx,Output=_Output>
-                    ,         Self : std::ops::Index<$idx,Output=_Output>
+            impl<$($($gen,)*)* _Output> core::ops::IndexMut<$idx> for $($ty)+
+                where _Variants!() : core::ops::IndexMut<$idx,Output=_Output>
+                    ,         Self : core::ops::Index<$idx,Output=_Output>
                       $($(, $pred)*)*
             {
                 fn index_mut( &mut self, index: $idx ) -> &mut _Output { _match!( _variant!().index_mut( index ))}
@@ -453,15 +839,196 @@ macro_rules! impl_trait {
     };
     ($(_impl!($($gen:tt),*))* std::ops::RangeBounds<$t:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         def_impls! {
-            impl<$($($gen,)*)*> std::ops::RangeBounds<$t> for $($ty)+
-                where _Variants!(): std::ops::RangeBounds<$t>
+            impl<$($($gen,)*)*> core::ops::RangeBounds<$t> for $($ty)+
+                where _Variants!(): core::ops::RangeBounds<$t>
                       $($(, $pred)*)*
             {
-                fn start_bound( &self ) -> std::ops::Bound<&T> { _match!( _variant!().start_bound() )}
-                fn end_bound( &self ) -> std::ops::Bound<&T> { _match!( _variant!().end_bound() )}
+                fn start_bound( &self ) -> core::ops::Bound<&T> { _match!( _variant!().start_bound() )}
+                fn end_bound( &self ) -> core::ops::Bound<&T> { _match!( _variant!().end_bound() )}
             }
         }
     };
+    ($(_impl!($($gen:tt),*))* futures_core::Stream _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)* _Item> futures_core::Stream for $($ty)+
+                where _Variants!(): futures_core::Stream<Item=_Item>
+                      $($(, $pred)*)*
+            {
+                type Item = _Item;
+                fn poll_next( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<Option<_Item>> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_next( cx )
+                    )
+                }
+                fn size_hint( &self ) -> (usize, Option<usize>) { _match!( _variant!().size_hint() )}
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* futures_io::AsyncBufRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> futures_io::AsyncBufRead for $($ty)+
+                where _Variants!() : futures_io::AsyncBufRead
+                    ,         Self : futures_io::AsyncRead
+                      $($(, $pred)*)*
+            {
+                fn poll_fill_buf( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> )
+                    -> core::task::Poll<std::io::Result<&[u8]>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_fill_buf( cx )
+                    )
+                }
+                fn consume( self: core::pin::Pin<&mut Self>, amt: usize ) {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.consume( amt )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* futures_io::AsyncRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> futures_io::AsyncRead for $($ty)+
+                where _Variants!(): futures_io::AsyncRead
+                      $($(, $pred)*)*
+            {
+                fn poll_read( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, buf: &mut [u8] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_read( cx, buf )
+                    )
+                }
+                fn poll_read_vectored( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, bufs: &mut [std::io::IoSliceMut<'_>] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_read_vectored( cx, bufs )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* futures_io::AsyncWrite _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> futures_io::AsyncWrite for $($ty)+
+                where _Variants!(): futures_io::AsyncWrite
+                      $($(, $pred)*)*
+            {
+                fn poll_write( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, buf: &[u8] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_write( cx, buf )
+                    )
+                }
+                fn poll_write_vectored( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, bufs: &[std::io::IoSlice<'_>] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_write_vectored( cx, bufs )
+                    )
+                }
+                fn poll_flush( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<std::io::Result<()>> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_flush( cx )
+                    )
+                }
+                fn poll_close( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<std::io::Result<()>> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_close( cx )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* tokio::io::AsyncBufRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> tokio::io::AsyncBufRead for $($ty)+
+                where _Variants!() : tokio::io::AsyncBufRead
+                    ,         Self : tokio::io::AsyncRead
+                      $($(, $pred)*)*
+            {
+                fn poll_fill_buf( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> )
+                    -> core::task::Poll<std::io::Result<&[u8]>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_fill_buf( cx )
+                    )
+                }
+                fn consume( self: core::pin::Pin<&mut Self>, amt: usize ) {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.consume( amt )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* tokio::io::AsyncRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> tokio::io::AsyncRead for $($ty)+
+                where _Variants!(): tokio::io::AsyncRead
+                      $($(, $pred)*)*
+            {
+                fn poll_read( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, buf: &mut tokio::io::ReadBuf<'_> )
+                    -> core::task::Poll<std::io::Result<()>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_read( cx, buf )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* tokio::io::AsyncWrite _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        def_impls! {
+            impl<$($($gen,)*)*> tokio::io::AsyncWrite for $($ty)+
+                where _Variants!(): tokio::io::AsyncWrite
+                      $($(, $pred)*)*
+            {
+                fn poll_write( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, buf: &[u8] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_write( cx, buf )
+                    )
+                }
+                fn poll_write_vectored( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_>, bufs: &[std::io::IoSlice<'_>] )
+                    -> core::task::Poll<std::io::Result<usize>>
+                {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_write_vectored( cx, bufs )
+                    )
+                }
+                fn is_write_vectored( &self ) -> bool { _match!( _variant!().is_write_vectored() )}
+                fn poll_flush( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<std::io::Result<()>> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_flush( cx )
+                    )
+                }
+                fn poll_shutdown( self: core::pin::Pin<&mut Self>, cx: &mut core::task::Context<'_> ) -> core::task::Poll<std::io::Result<()>> {
+                    _match!( unsafe{ self.get_unchecked_mut() } =>
+                        unsafe{ core::pin::Pin::new_unchecked( _variant!() )}.poll_shutdown( cx )
+                    )
+                }
+            }
+        }
+    };
+    ($(_impl!($($gen:tt),*))* Debug _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!{ $(_impl!($($gen),*))* std::fmt::Debug _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* Hash _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!{ $(_impl!($($gen),*))* std::hash::Hash _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    (@companion [$($imp:tt)*] _impl!($($gen:tt),*) $($rest:tt)+) => {
+        impl_trait!{ @companion [$($imp)* _impl!($($gen),*)] $($rest)+ }
+    };
+    (@companion [$($imp:tt)*] $($trait_:ident)::+ $(<$arg:tt>)? _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        $($trait_)::+!{ _trait!( $($trait_)::+ $(<$arg>)? ) $($imp)* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($($tt:tt)+) => {
+        impl_trait!{ @companion [] $($tt)+ }
+    };
 }
 
 /// The `impl_super_traits!{}` macro helps to implement the super trait(s) of the
@@ -469,6 +1036,9 @@ macro_rules! impl_trait {
 ///  will implement `FnMut` and `FnOnce` for `Type`, but **NOT** `Fn`.
 #[macro_export]
 macro_rules! impl_super_traits {
+    ($(_impl!($($gen:tt),*))* core::$($rest:tt)+) => {
+        impl_super_traits!{ $(_impl!($($gen),*))* std::$($rest)+ }
+    };
     ($(_impl!($($gen:tt),*))* DoubleEndedIterator _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         impl_trait!($(_impl!($($gen),*))* Iterator _for!($($ty)+) $(_where!($($pred)*))*);
     };
@@ -482,6 +1052,9 @@ macro_rules! impl_super_traits {
     ($(_impl!($($gen:tt),*))* FnMut<$args:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         impl_trait!($(_impl!($($gen),*))* FnOnce<$args> _for!($($ty)+) $(_where!($($pred)*))*);
     };
+    ($(_impl!($($gen:tt),*))* std::borrow::BorrowMut<$t:ty> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!($(_impl!($($gen),*))* std::borrow::Borrow<$t> _for!($($ty)+) $(_where!($($pred)*))*);
+    };
     ($(_impl!($($gen:tt),*))* std::error::Error _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         impl_trait!($(_impl!($($gen),*))* std::fmt::Debug _for!($($ty)+) $(_where!($($pred)*))*);
         impl_trait!($(_impl!($($gen),*))* std::fmt::Display _for!($($ty)+) $(_where!($($pred)*))*);
@@ -498,8 +1071,35 @@ macro_rules! impl_super_traits {
     ($(_impl!($($gen:tt),*))* std::ops::DerefMut _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
         impl_trait!($(_impl!($($gen),*))* std::ops::Deref _for!($($ty)+) $(_where!($($pred)*))*);
     };
-    ($(_impl!($($gen:tt),*))* std::ops::IndexMut _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
-        impl_trait!($(_impl!($($gen),*))* std::ops::Index _for!($($ty)+) $(_where!($($pred)*))*);
+    ($(_impl!($($gen:tt),*))* std::ops::IndexMut<$idx:ident> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!($(_impl!($($gen),*))* std::ops::Index<$idx> _for!($($ty)+) $(_where!($($pred)*))*);
+    };
+    ($(_impl!($($gen:tt),*))* futures_io::AsyncBufRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!($(_impl!($($gen),*))* futures_io::AsyncRead _for!($($ty)+) $(_where!($($pred)*))*);
+    };
+    ($(_impl!($($gen:tt),*))* tokio::io::AsyncBufRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!($(_impl!($($gen),*))* tokio::io::AsyncRead _for!($($ty)+) $(_where!($($pred)*))*);
+    };
+    ($(_impl!($($gen:tt),*))* BorrowMut<$t:tt> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        BorrowMut!{ _super_traits!( BorrowMut<$t> ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* Error _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        Error!{ _super_traits!( Error ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* FusedIterator _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        FusedIterator!{ _super_traits!( FusedIterator ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* TrustedLen _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        TrustedLen!{ _super_traits!( TrustedLen ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* BufRead _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        BufRead!{ _super_traits!( BufRead ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* DerefMut _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        DerefMut!{ _super_traits!( DerefMut ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($(_impl!($($gen:tt),*))* IndexMut<$idx:tt> _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        IndexMut!{ _super_traits!( IndexMut<$idx> ) $(_impl!($($gen),*))* _for!($($ty)+) $(_where!($($pred)*))* }
     };
     ($($_tt:tt)+) => {};
 }
@@ -515,3 +1115,150 @@ macro_rules! impl_all_traits {
         impl_super_traits!{ $($tt)+ }
     };
 }
+
+// The companion macros of traits in std which `impl_trait!{}` accepts by bare name,
+// e.g. `Read` imported from `std::io`. They are exported by `enumx::export` under
+// the traits' names, so a companion macro of a user-defined trait of the same name
+// shadows them wherever it is imported.
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_trait {
+    ($($path:ident)::+; _trait!( $_trait:ident $(<$arg:tt>)? ) $(_impl!($($gen:tt),*))* _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_trait!{ $(_impl!($($gen),*))* $($path)::+ $(<$arg>)? _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+    ($($path:ident)::+; _super_traits!( $_trait:ident $(<$arg:tt>)? ) $(_impl!($($gen:tt),*))* _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+        impl_super_traits!{ $(_impl!($($gen),*))* $($path)::+ $(<$arg>)? _for!($($ty)+) $(_where!($($pred)*))* }
+    };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Borrow {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::borrow::Borrow; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_BorrowMut {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::borrow::BorrowMut; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Error {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::error::Error; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Binary {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::Binary; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Display {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::Display; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_LowerHex {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::LowerHex; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Octal {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::Octal; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Pointer {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::Pointer; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_UpperHex {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::fmt::UpperHex; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Future {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::future::Future; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Hasher {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::hash::Hasher; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_BufRead {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::io::BufRead; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Read {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::io::Read; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Seek {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::io::Seek; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_FusedIterator {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::iter::FusedIterator; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_TrustedLen {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::iter::TrustedLen; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Deref {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::Deref; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_DerefMut {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::DerefMut; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Generator {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::Generator; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_Index {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::Index; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_IndexMut {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::IndexMut; $($tt)+ } };
+}
+
+#[doc( hidden )]
+#[macro_export]
+macro_rules! __enumx_std_RangeBounds {
+    ($($tt:tt)+) => { $crate::__enumx_std_trait!{ std::ops::RangeBounds; $($tt)+ } };
+}
@@ -0,0 +1,119 @@
+use enumx::export::*;
+use enumx::predefined::*;
+
+use futures::executor::block_on;
+
+use std::future::Future;
+
+fn countdown( from: u32 ) -> Enum2<impl Future<Output=u32>, std::future::Ready<u32>> {
+    if from == 0 {
+        Enum2::_1( std::future::ready( 0 ))
+    } else {
+        Enum2::_0( async move {
+            let half = async { from / 2 }.await;
+            half + from - half
+        })
+    }
+}
+
+#[test]
+fn future() {
+    assert_eq!( block_on( countdown( 0 )), 0 );
+    assert_eq!( block_on( countdown( 7 )), 7 );
+}
+
+#[sum]
+fn handle( request: u32 ) -> impl Future<Output=u32> {
+    match request {
+        0 => #[variant] std::future::ready( 0 ),
+        1 => #[variant] async { 1 },
+        _ => #[variant] async move { countdown( request ).await * 2 },
+    }
+}
+
+#[sum]
+async fn handle_async( request: u32 ) -> u32 {
+    let response = if request == 0 {
+        #[variant] async { 0 }
+    } else {
+        #[variant] async move { handle( request ).await + 1 }
+    };
+    response.await
+}
+
+#[sum]
+async fn describe( request: u32 ) -> impl std::fmt::Display {
+    if handle_async( request ).await == 0 {
+        #[variant] "zero"
+    } else {
+        #[variant] request
+    }
+}
+
+#[test]
+fn sum_futures() {
+    assert_eq!( block_on( handle( 0 )), 0 );
+    assert_eq!( block_on( handle( 1 )), 1 );
+    assert_eq!( block_on( handle( 7 )), 14 );
+
+    assert_eq!( block_on( handle_async( 0 )), 0 );
+    assert_eq!( block_on( handle_async( 7 )), 15 );
+
+    assert_eq!( block_on( describe( 0 )).to_string(), "zero" );
+    assert_eq!( block_on( describe( 7 )).to_string(), "7" );
+}
+
+#[cfg( feature = "futures" )]
+#[test]
+fn futures_stream_and_io() {
+    use futures::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, Cursor},
+        stream::{self, StreamExt},
+    };
+
+    let numbers = Enum2::<_, stream::Once<std::future::Ready<u8>>>::_0( stream::iter( vec![ 1_u8, 2, 3 ]));
+    assert_eq!( futures::Stream::size_hint( &numbers ), (3, Some(3)) );
+    assert_eq!( block_on( numbers.collect::<Vec<_>>() ), vec![ 1, 2, 3 ]);
+
+    let mut reader = Enum2::<Cursor<Vec<u8>>, &[u8]>::_1( b"enum\neXtensions" );
+    let mut line = String::new();
+    block_on( reader.read_line( &mut line )).unwrap();
+    assert_eq!( line, "enum\n" );
+    let mut rest = String::new();
+    block_on( reader.read_to_string( &mut rest )).unwrap();
+    assert_eq!( rest, "eXtensions" );
+
+    let mut writer = Enum2::<Cursor<Vec<u8>>, Vec<u8>>::_0( Cursor::new( vec![] ));
+    block_on( async {
+        writer.write_all( b"enumx" ).await.unwrap();
+        writer.close().await.unwrap();
+    });
+    match writer {
+        Enum2::_0( cursor ) => assert_eq!( cursor.into_inner(), b"enumx" ),
+        Enum2::_1(_) => unreachable!(),
+    }
+}
+
+#[cfg( feature = "tokio" )]
+#[test]
+fn tokio_io() {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    let mut reader = Enum2::<BufReader<&[u8]>, &[u8]>::_0( BufReader::new( b"enum\neXtensions" ));
+    let mut line = String::new();
+    block_on( reader.read_line( &mut line )).unwrap();
+    assert_eq!( line, "enum\n" );
+    let mut rest = String::new();
+    block_on( reader.read_to_string( &mut rest )).unwrap();
+    assert_eq!( rest, "eXtensions" );
+
+    let mut writer = Enum2::<Vec<u8>, tokio::io::Sink>::_0( vec![] );
+    block_on( async {
+        writer.write_all( b"enumx" ).await.unwrap();
+        writer.shutdown().await.unwrap();
+    });
+    match writer {
+        Enum2::_0( vec ) => assert_eq!( vec, b"enumx" ),
+        Enum2::_1(_) => unreachable!(),
+    }
+}
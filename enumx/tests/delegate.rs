@@ -0,0 +1,190 @@
+use enumx::export::*;
+use enumx::predefined::*;
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+mod shapes {
+    #[enumx::delegate( first_variant_consts )]
+    pub trait Shape {
+        type Unit;
+
+        const SIDES: u32;
+
+        fn area( &self ) -> f64;
+        fn scale( &mut self, factor: f64 );
+        fn unit( &self ) -> Self::Unit;
+        fn doubled( self ) -> Self;
+
+        fn name( &self ) -> String { "shape".to_owned() }
+        fn sides() -> u32 where Self: Sized { Self::SIDES }
+    }
+
+    #[derive( Debug, PartialEq )]
+    pub struct Square( pub f64 );
+
+    #[derive( Debug, PartialEq )]
+    pub struct Rect( pub f64, pub f64 );
+
+    impl Shape for Square {
+        type Unit = &'static str;
+        const SIDES: u32 = 4;
+        fn area( &self ) -> f64 { self.0 * self.0 }
+        fn scale( &mut self, factor: f64 ) { self.0 *= factor; }
+        fn unit( &self ) -> &'static str { "cm" }
+        fn doubled( self ) -> Self { Square( self.0 * 2.0 ) }
+        fn name( &self ) -> String { "square".to_owned() }
+    }
+
+    impl Shape for Rect {
+        type Unit = &'static str;
+        const SIDES: u32 = 4;
+        fn area( &self ) -> f64 { self.0 * self.1 }
+        fn scale( &mut self, factor: f64 ) { self.0 *= factor; self.1 *= factor; }
+        fn unit( &self ) -> &'static str { "m" }
+        fn doubled( self ) -> Self { Rect( self.0 * 2.0, self.1 * 2.0 ) }
+    }
+}
+
+use shapes::{Rect, Shape, Square};
+
+impl_trait!{ shapes::Shape _for!( Enum![2..=2] )}
+
+#[enumx::delegate]
+trait Convert<T> {
+    fn convert( &self, value: T ) -> String;
+}
+
+impl Convert<u8> for Square {
+    fn convert( &self, value: u8 ) -> String { format!( "{}x{}", value, self.0 ) }
+}
+
+impl Convert<u8> for Rect {
+    fn convert( &self, value: u8 ) -> String { format!( "{}x{}x{}", value, self.0, self.1 ) }
+}
+
+#[derive( Exchange, Debug, PartialEq )]
+enum Figure {
+    Square( Square ),
+    Rect( Rect ),
+}
+
+impl_trait!{ Convert _for!( _def!(
+    #[derive( Exchange, Debug, PartialEq )]
+    enum Figure {
+        Square( Square ),
+        Rect( Rect ),
+    }
+))}
+
+#[enumx::delegate]
+trait Countdown {
+    fn poll_count( self: Pin<&mut Self>, cx: &mut Context<'_> ) -> Poll<u32>;
+}
+
+struct Ready( u32 );
+
+impl Countdown for Ready {
+    fn poll_count( self: Pin<&mut Self>, _cx: &mut Context<'_> ) -> Poll<u32> { Poll::Ready( self.0 ) }
+}
+
+#[test]
+fn predefined() {
+    let mut shape = Enum2::<Square,Rect>::_1( Rect( 2.0, 3.0 ));
+    assert_eq!( shape.area(), 6.0 );
+    shape.scale( 2.0 );
+    assert_eq!( shape.area(), 24.0 );
+    assert_eq!( shape.unit(), "m" );
+    assert_eq!( shape.doubled(), Enum2::_1( Rect( 8.0, 12.0 )));
+
+    let square = Enum2::<Square,Rect>::_0( Square( 2.0 ));
+    assert_eq!( square.name(), "square" );
+    assert_eq!( Enum2::<Square,Rect>::_1( Rect( 1.0, 1.0 )).name(), "shape" );
+    assert_eq!( <Enum2<Square,Rect> as Shape>::SIDES, 4 );
+    assert_eq!( <Enum2<Square,Rect> as Shape>::sides(), 4 );
+}
+
+#[test]
+fn derived() {
+    assert_eq!( Figure::Square( Square( 2.0 )).convert( 3 ), "3x2" );
+    assert_eq!( Figure::Rect( Rect( 2.0, 1.0 )).convert( 3 ), "3x2x1" );
+}
+
+impl_trait!{ Countdown _for!( Enum![1..=1] )}
+
+#[test]
+fn pinned() {
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker( &waker );
+    let mut countdown = Enum1::_0( Ready( 7 ));
+    assert_eq!( Pin::new( &mut countdown ).poll_count( &mut cx ), Poll::Ready( 7 ));
+}
+
+mod named_like_std {
+    pub mod storage {
+        #[enumx::delegate]
+        pub trait Read {
+            fn read_all( &mut self ) -> String;
+        }
+
+        pub struct Tape( pub &'static str );
+        pub struct Disk( pub u8 );
+
+        impl Read for Tape {
+            fn read_all( &mut self ) -> String { self.0.to_owned() }
+        }
+
+        impl Read for Disk {
+            fn read_all( &mut self ) -> String { format!( "disk#{}", self.0 ) }
+        }
+    }
+
+    use enumx::export::*;
+    use self::storage::{Disk, Read, Tape};
+
+    #[sum]
+    pub fn medium( tape: bool ) -> impl Read {
+        if tape {
+            #[variant] Tape( "tape" )
+        } else {
+            #[variant] Disk( 1 )
+        }
+    }
+}
+
+mod std_by_bare_name {
+    use enumx::export::*;
+    use std::{error::Error, fmt::Display, io::Read};
+
+    #[sum]
+    pub fn label( cond: bool ) -> impl Display {
+        if cond { #[variant] 1_u8 } else { #[variant] "one" }
+    }
+
+    #[sum]
+    pub fn source( cond: bool ) -> impl Read {
+        if cond { #[variant] &b"ab"[..] } else { #[variant] std::io::empty() }
+    }
+
+    #[sum]
+    pub fn failure( cond: bool ) -> impl Error {
+        if cond { #[variant] std::fmt::Error } else { #[variant] std::io::Error::from( std::io::ErrorKind::Other ) }
+    }
+}
+
+#[test]
+fn traits_named_like_std() {
+    use named_like_std::storage::Read;
+    assert_eq!( named_like_std::medium( true ).read_all(), "tape" );
+    assert_eq!( named_like_std::medium( false ).read_all(), "disk#1" );
+
+    assert_eq!( std_by_bare_name::label( true ).to_string(), "1" );
+    assert_eq!( std_by_bare_name::label( false ).to_string(), "one" );
+
+    let mut read = String::new();
+    std::io::Read::read_to_string( &mut std_by_bare_name::source( true ), &mut read ).unwrap();
+    assert_eq!( read, "ab" );
+    assert_eq!( std_by_bare_name::failure( true ).to_string(), std::fmt::Error.to_string() );
+}
@@ -0,0 +1,83 @@
+#![cfg( feature = "serde" )]
+
+use enumx::export::*;
+use enumx::predefined::*;
+use enumx::encoding::{IndexTagged, TypeTagged, Untagged};
+
+use serde::{Deserialize, Serialize};
+
+#[derive( Exchange, Debug, PartialEq )]
+enum Value {
+    Int( i32 ),
+    Text( String ),
+    List( Vec<u8> ),
+}
+
+#[derive( Serialize, Deserialize, Debug, PartialEq )]
+struct Message {
+    #[serde( with = "enumx::encoding::untagged" )]
+    untagged: Value,
+    #[serde( with = "enumx::encoding::index_tagged" )]
+    index_tagged: Value,
+    #[serde( with = "enumx::encoding::type_tagged" )]
+    type_tagged: Value,
+}
+
+#[test]
+fn predefined() {
+    let value = Enum3::<i32,String,bool>::_1( "text".to_owned() );
+    let json = serde_json::to_string( &value ).unwrap();
+    assert_eq!( json, r#"{"_1":"text"}"# );
+    assert_eq!( serde_json::from_str::<Enum3<i32,String,bool>>( &json ).unwrap(), value );
+
+    assert_eq!( serde_json::from_str::<Enum3<i32,String,bool>>( r#"{"_2":true}"# ).unwrap(), Enum3::_2( true ));
+    assert!( serde_json::from_str::<Enum3<i32,String,bool>>( r#"{"_3":true}"# ).is_err() );
+    assert!( serde_json::from_str::<Enum3<i32,String,bool>>( r#"{"_0":true}"# ).is_err() );
+}
+
+#[test]
+fn wrappers() {
+    let value = Enum2::<u8,String>::_0( 7 );
+
+    assert_eq!( serde_json::to_string( &Untagged( &value )).unwrap(), "7" );
+    assert_eq!( serde_json::to_string( &IndexTagged( &value )).unwrap(), r#"{"_0":7}"# );
+    assert_eq!( serde_json::to_string( &TypeTagged( &value )).unwrap(), r#"{"u8":7}"# );
+
+    assert_eq!( serde_json::from_str::<Untagged<Enum2<u8,String>>>( "7" ).unwrap().0, value );
+    assert_eq!( serde_json::from_str::<Untagged<Enum2<u8,String>>>( r#""7""# ).unwrap().0, Enum2::_1( "7".to_owned() ));
+    assert!( serde_json::from_str::<Untagged<Enum2<u8,String>>>( "true" ).is_err() );
+    assert_eq!( serde_json::from_str::<TypeTagged<Enum2<u8,String>>>( r#"{"u8":7}"# ).unwrap().0, value );
+    assert!( serde_json::from_str::<TypeTagged<Enum2<u8,String>>>( r#"{"_0":7}"# ).is_err() );
+}
+
+#[test]
+fn derived() {
+    let message = Message {
+        untagged     : Value::List( vec![ 1, 2 ]),
+        index_tagged : Value::Text( "text".to_owned() ),
+        type_tagged  : Value::Int( 42 ),
+    };
+    let json = serde_json::to_string( &message ).unwrap();
+    assert_eq!( json, r#"{"untagged":[1,2],"index_tagged":{"_1":"text"},"type_tagged":{"i32":42}}"# );
+    assert_eq!( serde_json::from_str::<Message>( &json ).unwrap(), message );
+}
+
+struct Home;
+struct Work;
+
+#[derive( Exchange, Debug, PartialEq )]
+enum Address {
+    #[exchange( tag = Home )]
+    Home( String ),
+    #[exchange( tag = Work )]
+    Work( String ),
+}
+
+#[test]
+fn tagged() {
+    let address = Address::Work( "office".to_owned() );
+    let json = serde_json::to_string( &IndexTagged( &address )).unwrap();
+    assert_eq!( json, r#"{"_1":"office"}"# );
+    assert_eq!( serde_json::from_str::<IndexTagged<Address>>( &json ).unwrap().0, address );
+    assert_eq!( serde_json::to_string( &Untagged( &address )).unwrap(), r#""office""# );
+}
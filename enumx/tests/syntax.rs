@@ -9,6 +9,7 @@
 ))]
 
 use enumx::export::*;
+use enumx::coproduct::*;
 
 def_impls! {
     #[derive( Exchange )]
@@ -82,6 +83,335 @@ fn syntax() {
     };
 }
 
+#[test]
+fn borrowed_variants() {
+    #[enumx] fn describe( value: &Enum!( String, i32 )) -> String {
+        #[ty_pat] match value.as_variants_ref() {
+            TyPat::<&String>(s) => s.clone(),
+            TyPat::<&i32>(i) => i.to_string(),
+        }
+    }
+
+    let mut value: Enum!( String, i32 ) = 2018.exchange_into();
+    assert_eq!( describe( &value ), "2018" );
+
+    if let Enum2::_1( i ) = value.as_variants_mut() {
+        *i += 1;
+    }
+    assert_eq!( describe( &value ), "2019" );
+
+    let _: Enum!( &i32, bool, &String ) = value.as_variants_ref().exchange_into();
+}
+
+#[test]
+fn borrowed_enum() {
+    #[enumx] fn increase( mut value: Enum!( String, i32 )) -> Enum!( String, i32 ) {
+        #[ty_pat] match &mut value {
+            String(s) => s.push( '!' ),
+            i32(i) => *i += 1,
+        }
+        value
+    }
+
+    #[enumx] fn describe( value: Enum!( String, i32 )) -> String {
+        let description = #[ty_pat] match &value {
+            String(s) => s.clone(),
+            i32(i) => i.to_string(),
+        };
+        let len = #[ty_pat] match value {
+            String( ref s ) => s.len(),
+            i32(_) => 0,
+        };
+        format!( "{}:{}", description, len )
+    }
+
+    assert_eq!( describe( increase( 2018.exchange_into() )), "2019:0" );
+    assert_eq!( describe( increase( String::from( "enumx" ).exchange_into() )), "enumx!:6" );
+}
+
+#[test]
+fn borrowed_enum_param() {
+    #[enumx] fn describe( value: &Enum!( String, i32 )) -> String {
+        #[ty_pat] match value {
+            String(s) => s.clone(),
+            i32(i) => i.to_string(),
+        }
+    }
+
+    #[enumx] fn increase( value: &mut Enum!( String, i32 )) {
+        #[ty_pat] match &mut *value {
+            String(s) => s.push( '!' ),
+            i32(i) => *i += 1,
+        }
+        #[ty_pat] match value {
+            String(s) => s.push( '?' ),
+            i32(i) => *i *= 2,
+        }
+    }
+
+    let mut value: Enum!( String, i32 ) = 2018.exchange_into();
+    increase( &mut value );
+    assert_eq!( describe( &value ), "4038" );
+
+    let mut value: Enum!( String, i32 ) = String::from( "enumx" ).exchange_into();
+    increase( &mut value );
+    assert_eq!( describe( &value ), "enumx!?" );
+}
+
+#[test]
+fn borrowed_struct_variants() {
+    #[derive( Exchange, Debug, PartialEq )]
+    enum Event {
+        Timeout,
+        Io{ path: String, code: i32 },
+        Text( String ),
+    }
+
+    #[enumx] fn retry( mut event: Event ) -> Event {
+        #[ty_pat] match &mut event {
+            TyPat::<()>(_) => (),
+            TyPat::<(&mut String, &mut i32)>(( _, code )) => *code += 1,
+            TyPat::<&mut String>( text ) => text.push( '!' ),
+        }
+        event
+    }
+
+    #[enumx] fn describe( event: Event ) -> String {
+        #[ty_pat] match &event {
+            TyPat::<&()>(_) => "timeout".to_owned(),
+            TyPat::<(&String, &i32)>(( path, code )) => format!( "{}:{}", path, code ),
+            TyPat::<&String>( text ) => text.clone(),
+        }
+    }
+
+    assert_eq!( describe( retry( Event::Timeout )), "timeout" );
+    assert_eq!( describe( retry( Event::Io{ path: "/tmp".to_owned(), code: 2 })), "/tmp:3" );
+    assert_eq!( describe( retry( Event::Text( "hi".to_owned() ))), "hi!" );
+}
+
+#[test]
+fn rich_patterns() {
+    struct Pair( i32, bool );
+    struct Point{ x: i32, y: i32 }
+    struct Code( i32 );
+
+    #[enumx] fn describe( value: Enum!( Pair, Point, Code )) -> String {
+        #[ty_pat] match value {
+            Pair( n, true ) | Code( Code( n )) if n < 0 => "negative".to_owned(),
+            Pair( n, true ) => format!( "pair {}", n ),
+            Pair( _, false ) => "no pair".to_owned(),
+            Point{ x: 0, y } => format!( "y {}", y ),
+            p @ Point{ .. } if p.x == p.y => format!( "diagonal {}", p.x ),
+            Point{ x, .. } => format!( "x {}", x ),
+            c @ Code(_) => c.0.to_string(),
+        }
+    }
+
+    assert_eq!( describe( Pair( -1, true ).exchange_into() ), "negative" );
+    assert_eq!( describe( Pair( 3, true ).exchange_into() ), "pair 3" );
+    assert_eq!( describe( Pair( 3, false ).exchange_into() ), "no pair" );
+    assert_eq!( describe( Point{ x: 0, y: 5 }.exchange_into() ), "y 5" );
+    assert_eq!( describe( Point{ x: 2, y: 2 }.exchange_into() ), "diagonal 2" );
+    assert_eq!( describe( Point{ x: 3, y: 1 }.exchange_into() ), "x 3" );
+    assert_eq!( describe( Code( -7 ).exchange_into() ), "negative" );
+    assert_eq!( describe( Code( 7 ).exchange_into() ), "7" );
+}
+
+#[test]
+fn coproduct() {
+    type Units = Enum!(
+        [();  0], [();  1], [();  2], [();  3], [();  4], [();  5], [();  6], [();  7], [();  8], [();  9],
+        [(); 10], [(); 11], [(); 12], [(); 13], [(); 14], [(); 15], [(); 16], [(); 17], [(); 18], [(); 19],
+        [(); 20], [(); 21], [(); 22], [(); 23], [(); 24], [(); 25], [(); 26], [(); 27], [(); 28], [(); 29],
+        [(); 30], [(); 31], [(); 32], [(); 33], [(); 34], [(); 35], [(); 36], [(); 37], [(); 38], [(); 39],
+        String
+    );
+
+    #[enumx] fn first_and_last( units: Units ) -> Enum!(
+        usize,    [();  1], [();  2], [();  3], [();  4], [();  5], [();  6], [();  7], [();  8], [();  9],
+        [(); 10], [(); 11], [(); 12], [(); 13], [(); 14], [(); 15], [(); 16], [(); 17], [(); 18], [(); 19],
+        [(); 20], [(); 21], [(); 22], [(); 23], [(); 24], [(); 25], [(); 26], [(); 27], [(); 28], [(); 29],
+        [(); 30], [(); 31], [(); 32], [(); 33], [(); 34], [(); 35], [(); 36], [(); 37], [(); 38],
+        String
+    ) {
+        #[ty_pat(gen_variants)] match units {
+            TyPat::<[(); 0]>(_) => 0_usize.exchange_into(),
+            TyPat::<[(); 39]>(_) => 39_usize.exchange_into(),
+        }
+    }
+
+    let units: Units = [(); 39].exchange_into();
+    match first_and_last( units ) {
+        Coprod::Here( n ) => assert_eq!( n, 39 ),
+        _ => unreachable!(),
+    }
+
+    let units = Units::from_variant( "rust".to_owned() );
+    let units = first_and_last( units );
+    let _: &String = units.get_variant().unwrap();
+    assert!( units.is_variant::<String>() );
+    assert_eq!( units.take_by_type::<String>().ok(), Some( "rust".to_owned() ));
+
+    let units = Units::exchange_from( Enum2::<String, [(); 1]>::_1( [(); 1] ));
+    if let Coprod::Here(_) = first_and_last( units ) {
+        unreachable!();
+    }
+
+    let _: Coprod!( String, bool ) = Coprod::<bool, Coprod<String, CNil>>::exchange_from( true ).exchange_into();
+}
+
+// `Enum!()` of 17..=32 types is lowered to `Coprod!()` unless the feature "enum32" is on.
+#[cfg( not( feature="enum32" ))]
+#[test]
+fn beyond_predefined() {
+    type Units = Enum!(
+        [();  0], [();  1], [();  2], [();  3], [();  4], [();  5], [();  6], [();  7], [();  8], [();  9],
+        [(); 10], [(); 11], [(); 12], [(); 13], [(); 14], [(); 15], [(); 16], [(); 17], String
+    );
+
+    #[enumx] fn describe( units: Units ) -> Enum!(
+        &'static str, [();  1], [();  2], [();  3], [();  4], [();  5], [();  6], [();  7], [();  8], [();  9],
+        [(); 10], [(); 11], [(); 12], [(); 13], [(); 14], [(); 15], [(); 16], [(); 17]
+    ) {
+        #[ty_pat(gen_variants)] match units {
+            TyPat::<[(); 0]>(_) => "first".exchange_into(),
+            TyPat::<String>(_) => "last".exchange_into(),
+        }
+    }
+
+    assert_eq!( describe( [(); 0].exchange_into() ).get_variant(), Some( &"first" ));
+    assert_eq!( describe( "rust".to_owned().exchange_into() ).get_variant(), Some( &"last" ));
+    assert_eq!( describe( [(); 17].exchange_into() ).get_variant(), Some( &[(); 17] ));
+
+    let units: Units = Coprod::Here( [(); 0] );
+    assert!( units.is_variant::<[(); 0]>() );
+}
+
+#[test]
+fn sum_methods() {
+    trait Strategy {
+        fn flag( &self ) -> bool;
+
+        fn numbers( &self ) -> impl Iterator<Item=u8>;
+
+        #[sum]
+        fn letters( &self ) -> impl Iterator<Item=char> {
+            if self.flag() {
+                #[variant] "ab".chars()
+            } else {
+                #[variant] std::iter::empty()
+            }
+        }
+    }
+
+    struct Fast;
+    struct Slow;
+
+    #[sum]
+    impl Strategy for Fast {
+        fn flag( &self ) -> bool { true }
+
+        fn numbers( &self ) -> impl Iterator<Item=u8> {
+            if self.flag() {
+                #[variant] 0..3
+            } else {
+                #[variant] std::iter::once( 0 )
+            }
+        }
+    }
+
+    #[sum( auto )]
+    impl Strategy for Slow {
+        fn flag( &self ) -> bool { false }
+
+        fn numbers( &self ) -> impl Iterator<Item=u8> {
+            if self.flag() {
+                return vec![ 1, 2 ].into_iter();
+            }
+            std::iter::once( 7 )
+        }
+    }
+
+    #[sum]
+    impl Slow {
+        fn numbers_and_letters( &self, cond: bool ) -> impl Iterator<Item=String> + '_ {
+            if cond {
+                #[variant] self.numbers().map( |n| n.to_string() )
+            } else {
+                #[variant] self.letters().map( String::from )
+            }
+        }
+    }
+
+    assert_eq!( Fast.numbers().collect::<Vec<_>>(), vec![ 0, 1, 2 ]);
+    assert_eq!( Fast.letters().collect::<String>(), "ab" );
+    assert_eq!( Slow.numbers().collect::<Vec<_>>(), vec![ 7 ]);
+    assert_eq!( Slow.letters().count(), 0 );
+    assert_eq!( Slow.numbers_and_letters( true ).collect::<Vec<_>>(), vec![ "7" ]);
+}
+
+#[test]
+fn sum_associated_types() {
+    use enumx::predefined::{Enum2, Enum3};
+    use std::{iter::Once, ops::Range, vec::IntoIter};
+
+    #[sum]
+    trait Strategy {
+        fn flag( &self ) -> bool;
+
+        fn numbers( &self ) -> impl Iterator<Item=u8>;
+
+        fn sorted_numbers( &self ) -> impl Iterator<Item=u8> + Clone + 'static;
+    }
+
+    struct Fast;
+    struct Slow;
+
+    #[sum]
+    impl Strategy for Fast {
+        type Numbers = Enum2<Range<u8>, Once<u8>>;
+        type SortedNumbers = Range<u8>;
+
+        fn flag( &self ) -> bool { true }
+
+        fn numbers( &self ) -> Self::Numbers {
+            if self.flag() {
+                #[variant] 0..3
+            } else {
+                #[variant] std::iter::once( 0 )
+            }
+        }
+
+        fn sorted_numbers( &self ) -> Self::SortedNumbers { 0..3 }
+    }
+
+    #[sum( auto )]
+    impl Strategy for Slow {
+        type Numbers = Enum3<IntoIter<u8>, Range<u8>, Once<u8>>;
+        type SortedNumbers = Once<u8>;
+
+        fn flag( &self ) -> bool { false }
+
+        fn numbers( &self ) -> Self::Numbers {
+            if self.flag() {
+                return vec![ 1, 2 ].into_iter();
+            }
+            match 7 {
+                0 => 0..0,
+                n => std::iter::once( n ),
+            }
+        }
+
+        fn sorted_numbers( &self ) -> Self::SortedNumbers { std::iter::once( 7 ) }
+    }
+
+    let strategies: Vec<Box<dyn Strategy<Numbers=_, SortedNumbers=_>>> = vec![ Box::new( Fast )];
+    assert_eq!( strategies[0].numbers().collect::<Vec<_>>(), vec![ 0, 1, 2 ]);
+    assert_eq!( Fast.sorted_numbers().max(), Some( 2 ));
+    assert_eq!( Slow.numbers().collect::<Vec<_>>(), vec![ 7 ]);
+    assert_eq!( Slow.sorted_numbers().clone().count(), 1 );
+}
+
 #[test]
 fn sum_syntax() {
     #[sum] fn _if( cond: bool ) -> impl Clone {
@@ -102,6 +432,39 @@ fn sum_syntax() {
         }
     }
 
+    #[cfg( feature = "unstable" )]
+    {
+        let closure = #[sum] |cond: bool| -> impl Iterator<Item=u8> {
+            if cond {
+                #[variant] vec![ 1_u8, 2 ].into_iter()
+            } else {
+                #[variant] std::iter::once( 0_u8 )
+            }
+        };
+        assert_eq!( closure( true ).count(), 2 );
+    }
+
+    // A closure inside a `#[sum]` fn is expanded by the fn's attribute, on stable Rust.
+    #[sum]
+    fn _closure_in_fn( cond: bool ) -> String {
+        let closure = #[sum] |cond: bool| -> impl Iterator<Item=u8> {
+            if cond {
+                #[variant] vec![ 1_u8, 2 ].into_iter()
+            } else {
+                #[variant] std::iter::once( 0_u8 )
+            }
+        };
+        let auto = #[sum( auto )] |n: u8| -> impl Clone + Debug {
+            if n == 0 {
+                return "zero";
+            }
+            n
+        };
+        format!( "{:?}", auto( closure( cond ).count() as u8 - 1 ))
+    }
+    assert_eq!( _closure_in_fn( true ), "1" );
+    assert_eq!( _closure_in_fn( false ), r#""zero""# );
+
     struct ESI0;
     impl Iterator for ESI0 {
         type Item = ();
@@ -126,6 +489,109 @@ fn sum_syntax() {
         }
     }
 
+    #[sum]
+    fn _bytes( cond: bool ) -> impl Iterator<Item=u8> + Send + std::fmt::Debug {
+        if cond {
+            #[variant] vec![ 1_u8, 2 ].into_iter()
+        } else {
+            #[variant] std::iter::once( 0_u8 )
+        }
+    }
+    assert_eq!( _bytes( true ).collect::<Vec<_>>(), vec![ 1, 2 ]);
+    assert_eq!( _bytes( false ).size_hint(), (1, Some(1)) );
+    fn _assert_send<T: Send>( _: T ) {}
+    _assert_send( _bytes( true ));
+
+    #[sum]
+    fn _words<'a>( text: &'a str, cond: bool ) -> impl Iterator<Item=&'a str> + Clone + 'a {
+        if cond {
+            #[variant] text.split_whitespace()
+        } else {
+            #[variant] text.lines()
+        }
+    }
+    assert_eq!( _words( "enum eXtensions", true ).clone().count(), 2 );
+
+    use std::fmt::Debug;
+    #[sum]
+    fn _text( cond: bool ) -> impl AsRef<str> + Debug + Sync {
+        if cond {
+            #[variant] "enumx"
+        } else {
+            #[variant] String::from( "cex" )
+        }
+    }
+    assert_eq!( _text( false ).as_ref(), "cex" );
+    assert_eq!( format!( "{:?}", _text( true )), r#""enumx""# );
+
+    #[sum( auto )]
+    fn _auto( branch: i32 ) -> impl Clone + Debug {
+        if branch < 0 {
+            return "negative";
+        }
+        match branch {
+            0 => 0_u8,
+            1 => {
+                let one = String::from( "one" );
+                one
+            },
+            2 => if branch % 2 == 0 { 2.0_f32 } else { unreachable!() },
+            _ => panic!("unexpected branch"),
+        }
+    }
+    assert_eq!( format!( "{:?}", _auto( -1 )), r#""negative""# );
+    assert_eq!( format!( "{:?}", _auto( 0 ).clone() ), "0" );
+    assert_eq!( format!( "{:?}", _auto( 1 )), r#""one""# );
+    assert_eq!( format!( "{:?}", _auto( 2 )), "2.0" );
+
+    #[sum( auto )]
+    fn _auto_merged( branch: i32 ) -> impl Clone + Debug {
+        match branch {
+            0 => #[variant( int )] 0_i32,
+            1 => #[variant( int )] 1_i32,
+            _ => "other",
+        }
+    }
+    assert_eq!( format!( "{:?}", _auto_merged( 1 )), "1" );
+    assert_eq!( format!( "{:?}", _auto_merged( 2 )), r#""other""# );
+
+    #[sum( auto )]
+    fn _auto_loop( n: i32 ) -> impl Clone + Debug {
+        let mut i = 0;
+        'outer: loop {
+            for j in 0..3 {
+                if i + j == n {
+                    break 'outer j as u8;
+                }
+                if i + j > n {
+                    break;
+                }
+            }
+            i += 3;
+            if i > 5 {
+                break "none";
+            }
+        }
+    }
+    assert_eq!( format!( "{:?}", _auto_loop( 4 )), "1" );
+    assert_eq!( format!( "{:?}", _auto_loop( 9 )), r#""none""# );
+
+    #[sum( auto )]
+    fn _auto_loop_without_break( n: i32 ) -> impl Clone + Debug {
+        let mut i = n;
+        loop {
+            if i == 0 {
+                return "zero";
+            }
+            if i > 10 {
+                return i;
+            }
+            i += 1;
+        }
+    }
+    assert_eq!( format!( "{:?}", _auto_loop_without_break( -3 )), r#""zero""# );
+    assert_eq!( format!( "{:?}", _auto_loop_without_break( 11 )), "11" );
+
     #[sum( ok  => impl Clone )]
     #[sum( err => impl Clone )]
     fn _sum_okeys_and_errors( branch: i32 ) -> Result<impl Clone, impl Clone> {
@@ -150,10 +616,24 @@ fn sum_syntax() {
     }
 }
 
+#[test]
+fn by_type_with_iterator() {
+    use enumx::predefined::Enum2;
+
+    let iter = Enum2::<std::ops::Range<u32>, std::vec::IntoIter<u32>>::_0( 0..10 );
+    assert_eq!( iter.take(3).collect::<Vec<_>>(), vec![ 0, 1, 2 ]);
+
+    let iter = Enum2::<std::ops::Range<u32>, std::vec::IntoIter<u32>>::_1( vec![ 7, 8 ].into_iter() );
+    assert!( iter.is_variant::<std::vec::IntoIter<u32>>() );
+    assert_eq!( iter.take_by_type::<std::vec::IntoIter<u32>>().unwrap().collect::<Vec<_>>(), vec![ 7, 8 ]);
+}
+
 #[test]
 fn impl_for_predefined() {
     impl_trait!{ _impl!(T) AsRef<T> _for!( Enum![1..=3] )}
     impl_trait!{ _impl!(T) AsMut<T> _for!( Enum![1..=3] )}
+    impl_trait!{ _impl!(T) AsRef<[T]> _for!( Enum![1..=3] )}
+    impl_trait!{ _impl!(T) AsMut<[T]> _for!( Enum![1..=3] )}
     impl_trait!{ DoubleEndedIterator _for!( Enum![1..=3] )}
     impl_trait!{ ExactSizeIterator _for!( Enum![1..=3] )}
     impl_trait!{ _impl!(A) Extend<A> _for!( Enum![1..=3] )}
@@ -162,9 +642,19 @@ fn impl_for_predefined() {
     impl_trait!{ _impl!(Args) Fn<Args> _for!( Enum![1..=3] )}
 
     impl_trait!{ Iterator _for!( Enum![1..=3] )}
+    impl_trait!{ std::borrow::Borrow<str> _for!( Enum![1..=3] )}
+    impl_trait!{ std::borrow::BorrowMut<str> _for!( Enum![1..=3] )}
     impl_trait!{ std::error::Error _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::Binary _for!( Enum![1..=3] )}
     impl_trait!{ std::fmt::Debug _for!( Enum![1..=3] )}
     impl_trait!{ std::fmt::Display _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::LowerHex _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::Octal _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::Pointer _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::UpperHex _for!( Enum![1..=3] )}
+    impl_trait!{ std::fmt::Write _for!( Enum![1..=3] )}
+    impl_trait!{ std::hash::Hash _for!( Enum![1..=3] )}
+    impl_trait!{ std::hash::Hasher _for!( Enum![1..=3] )}
     impl_trait!{ std::iter::FusedIterator _for!( Enum![1..=3] )}
 
     #[cfg( feature = "unstable" )]
@@ -237,3 +727,147 @@ fn impl_trait_for_def() {
         )
     }
 }
+
+#[test]
+fn io_forwarding() {
+    use std::io::{BufRead, Cursor, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+    // Records the calls, to tell the forwarded methods from the default ones.
+    #[derive( Default )]
+    struct Traced {
+        calls: Vec<&'static str>,
+    }
+
+    impl Read for Traced {
+        fn read( &mut self, _buf: &mut [u8] ) -> std::io::Result<usize> { self.calls.push( "read" ); Ok(0) }
+        fn read_vectored( &mut self, _bufs: &mut [IoSliceMut<'_>] ) -> std::io::Result<usize> { self.calls.push( "read_vectored" ); Ok(0) }
+        fn read_to_end( &mut self, _buf: &mut Vec<u8> ) -> std::io::Result<usize> { self.calls.push( "read_to_end" ); Ok(0) }
+        fn read_to_string( &mut self, _buf: &mut String ) -> std::io::Result<usize> { self.calls.push( "read_to_string" ); Ok(0) }
+        fn read_exact( &mut self, _buf: &mut [u8] ) -> std::io::Result<()> { self.calls.push( "read_exact" ); Ok(()) }
+    }
+
+    impl BufRead for Traced {
+        fn fill_buf( &mut self ) -> std::io::Result<&[u8]> { self.calls.push( "fill_buf" ); Ok( &[] )}
+        fn consume( &mut self, _amt: usize ) { self.calls.push( "consume" ); }
+        fn read_until( &mut self, _byte: u8, _buf: &mut Vec<u8> ) -> std::io::Result<usize> { self.calls.push( "read_until" ); Ok(0) }
+        fn read_line( &mut self, _buf: &mut String ) -> std::io::Result<usize> { self.calls.push( "read_line" ); Ok(0) }
+    }
+
+    impl Seek for Traced {
+        fn seek( &mut self, _pos: SeekFrom ) -> std::io::Result<u64> { self.calls.push( "seek" ); Ok(0) }
+        fn rewind( &mut self ) -> std::io::Result<()> { self.calls.push( "rewind" ); Ok(()) }
+        fn stream_position( &mut self ) -> std::io::Result<u64> { self.calls.push( "stream_position" ); Ok(0) }
+    }
+
+    impl Write for Traced {
+        fn write( &mut self, _buf: &[u8] ) -> std::io::Result<usize> { self.calls.push( "write" ); Ok(0) }
+        fn flush( &mut self ) -> std::io::Result<()> { self.calls.push( "flush" ); Ok(()) }
+        fn write_vectored( &mut self, _bufs: &[IoSlice<'_>] ) -> std::io::Result<usize> { self.calls.push( "write_vectored" ); Ok(0) }
+        fn write_all( &mut self, _buf: &[u8] ) -> std::io::Result<()> { self.calls.push( "write_all" ); Ok(()) }
+        fn write_fmt( &mut self, _fmt: std::fmt::Arguments<'_> ) -> std::io::Result<()> { self.calls.push( "write_fmt" ); Ok(()) }
+    }
+
+    enum Reader {
+        Owned( Cursor<Vec<u8>> ),
+        Static( Cursor<&'static [u8]> ),
+        Traced( Traced ),
+    }
+    impl_all_traits! { std::io::BufRead _for!(
+        _def!{ enum Reader {
+            Owned( Cursor<Vec<u8>> ),
+            Static( Cursor<&'static [u8]> ),
+            Traced( Traced ),
+        }}
+    )}
+    impl_trait! { std::io::Seek _for!(
+        _def!{ enum Reader {
+            Owned( Cursor<Vec<u8>> ),
+            Static( Cursor<&'static [u8]> ),
+            Traced( Traced ),
+        }}
+    )}
+
+    let mut reader = Reader::Static( Cursor::new( b"enum\neXtensions" ));
+    let mut line = String::new();
+    assert_eq!( reader.read_line( &mut line ).unwrap(), 5 );
+    assert_eq!( line, "enum\n" );
+    assert_eq!( reader.stream_position().unwrap(), 5 );
+
+    let mut buf = [0; 2];
+    reader.read_exact( &mut buf ).unwrap();
+    assert_eq!( &buf, b"eX" );
+
+    reader.rewind().unwrap();
+    let mut text = String::new();
+    reader.read_to_string( &mut text ).unwrap();
+    assert_eq!( text, "enum\neXtensions" );
+
+    let mut reader = Reader::Owned( Cursor::new( b"a,b".to_vec() ));
+    reader.seek( SeekFrom::Start(2) ).unwrap();
+    let mut rest = vec![];
+    assert_eq!( reader.read_until( b',', &mut rest ).unwrap(), 1 );
+    assert_eq!( rest, b"b" );
+
+    let mut reader = Reader::Traced( Traced::default() );
+    let mut buf = [0; 2];
+    assert_eq!( reader.read( &mut buf ).unwrap(), 0 );
+    assert_eq!( reader.read_vectored( &mut [IoSliceMut::new( &mut buf )]).unwrap(), 0 );
+    reader.read_exact( &mut buf ).unwrap();
+    reader.read_to_end( &mut vec![] ).unwrap();
+    reader.read_to_string( &mut String::new() ).unwrap();
+    reader.fill_buf().unwrap();
+    reader.consume(0);
+    reader.read_until( b',', &mut vec![] ).unwrap();
+    reader.read_line( &mut String::new() ).unwrap();
+    reader.seek( SeekFrom::Start(0) ).unwrap();
+    reader.rewind().unwrap();
+    reader.stream_position().unwrap();
+    match reader {
+        Reader::Traced( traced ) => assert_eq!( traced.calls, vec![
+            "read", "read_vectored", "read_exact", "read_to_end", "read_to_string",
+            "fill_buf", "consume", "read_until", "read_line",
+            "seek", "rewind", "stream_position",
+        ]),
+        _ => unreachable!(),
+    }
+
+    enum Writer {
+        Vec( Vec<u8> ),
+        Cursor( Cursor<Vec<u8>> ),
+        Traced( Traced ),
+    }
+    impl_trait! { std::io::Write _for!(
+        _def!{ enum Writer {
+            Vec( Vec<u8> ),
+            Cursor( Cursor<Vec<u8>> ),
+            Traced( Traced ),
+        }}
+    )}
+
+    let mut writer = Writer::Cursor( Cursor::new( vec![] ));
+    writer.write_all( b"enum" ).unwrap();
+    write!( writer, "{}", 1 ).unwrap();
+    writer.flush().unwrap();
+    match writer {
+        Writer::Cursor( cursor ) => assert_eq!( cursor.into_inner(), b"enum1" ),
+        _ => unreachable!(),
+    }
+
+    let mut writer = Writer::Vec( vec![] );
+    writer.write_all( b"enumx" ).unwrap();
+    match writer {
+        Writer::Vec( vec ) => assert_eq!( vec, b"enumx" ),
+        _ => unreachable!(),
+    }
+
+    let mut writer = Writer::Traced( Traced::default() );
+    assert_eq!( writer.write( b"e" ).unwrap(), 0 );
+    assert_eq!( writer.write_vectored( &[IoSlice::new( b"n" )]).unwrap(), 0 );
+    writer.write_all( b"um" ).unwrap();
+    writeln!( writer ).unwrap();
+    writer.flush().unwrap();
+    match writer {
+        Writer::Traced( traced ) => assert_eq!( traced.calls, vec![ "write", "write_vectored", "write_all", "write_fmt", "flush" ]),
+        _ => unreachable!(),
+    }
+}
@@ -8,7 +8,7 @@
 #![recursion_limit="128"]
 
 extern crate proc_macro;
-use self::proc_macro::{TokenStream, TokenTree};
+use self::proc_macro::TokenStream;
 
 use indexmap::{
     IndexMap,
@@ -16,12 +16,15 @@ use indexmap::{
 };
 
 use quote::{
+    ToTokens,
     quote,
     quote_spanned,
 };
 
 use std::{
     cell::Cell,
+    collections::hash_map::DefaultHasher,
+    fmt::Display,
     hash::{Hash, Hasher},
     iter::FromIterator,
     mem,
@@ -30,31 +33,45 @@ use std::{
 
 use syn::{
     Attribute,
+    Block,
+    DataEnum,
     DeriveInput,
     Expr,
+    ExprBlock,
     ExprClosure,
+    ExprGroup,
     ExprMacro,
     ExprRange,
     Fields,
+    FnArg,
+    GenericArgument,
     GenericParam,
     Generics,
     Ident,
     ImplItem,
+    Index,
     Item,
     ItemEnum,
     ItemFn,
     ItemImpl,
     ItemMacro,
+    ItemTrait,
+    Lifetime,
     Lit,
     MacroDelimiter,
+    Member,
     Pat,
     Path,
     PathArguments,
     RangeLimits,
     ReturnType,
+    Signature,
     Stmt,
     Token,
     Type,
+    TraitBound,
+    TraitItem,
+    TraitBoundModifier,
     TypeParamBound,
     TypeMacro,
     TypeParam,
@@ -62,6 +79,7 @@ use syn::{
     Variant,
     Visibility,
     WhereClause,
+    WherePredicate,
     braced,
     bracketed,
     parse_macro_input,
@@ -76,12 +94,6 @@ use syn::{
 extern crate proc_macro2;
 use proc_macro2::Span;
 
-macro_rules! syntax_error {
-    () => {
-        panic!( "{}", "`enum`s deriving `FromVariant`/`Proto` should be in the form of \"enum MyEnum { Foo(Type), Bar(AnotherType),... }\"" );
-    }
-}
-
 macro_rules! parse_quote_spanned {
     ( $span:expr => $($tt:tt)+ ) => {{
         let quoted = quote_spanned!( $span => $($tt)+ );
@@ -89,6 +101,44 @@ macro_rules! parse_quote_spanned {
     }};
 }
 
+/// Errors collected in one macro invocation, to be reported as `compile_error!`s altogether.
+#[derive( Default )]
+struct Errors( Option<syn::Error> );
+
+impl Errors {
+    fn push( &mut self, error: syn::Error ) {
+        match &mut self.0 {
+            Some( errors ) => errors.combine( error ),
+            None => self.0 = Some( error ),
+        }
+    }
+
+    fn error( &mut self, tokens: impl ToTokens, message: impl Display ) {
+        self.push( syn::Error::new_spanned( tokens, message ));
+    }
+
+    fn extend( &mut self, errors: Errors ) {
+        if let Some( error ) = errors.0 {
+            self.push( error );
+        }
+    }
+
+    fn is_empty( &self ) -> bool {
+        self.0.is_none()
+    }
+
+    fn into_result( self ) -> syn::Result<()> {
+        match self.0 {
+            Some( error ) => Err( error ),
+            None => Ok(()),
+        }
+    }
+
+    fn to_compile_error( &self ) -> proc_macro2::TokenStream {
+        self.0.as_ref().map( syn::Error::to_compile_error ).unwrap_or_default()
+    }
+}
+
 /// `Enum!( Type0, Type1, ..., TypeM )` denotes a predefined enum, the definition of which looks like:
 ///
 /// ```rust
@@ -107,10 +157,16 @@ macro_rules! parse_quote_spanned {
 /// ```rust
 /// pub enum Enum0 {}
 /// ```
+///
+/// `Enum!()` of more types than the predefined enums, i.e. 16 by default or 32 with the
+/// crate feature "enum32" of enumx, denotes `Coprod!()` of these types.
 #[proc_macro]
 #[allow( non_snake_case )]
 pub fn Enum( input: TokenStream ) -> TokenStream {
     let type_list = parse_macro_input!( input as TypeList );
+    if type_list.0.len() > MAX_PREDEFINED {
+        return coproduct( type_list.0 ).into();
+    }
     let types = type_list.0.iter();
     let name = make_ident( &format!( "Enum{}", types.len() ));
     let expanded = if types.len() == 0 {
@@ -121,6 +177,159 @@ pub fn Enum( input: TokenStream ) -> TokenStream {
     expanded.into()
 }
 
+/// `Flat!( Type0, Type1, ..., TypeM )` denotes a predefined enum the same as `Enum!()`, except that variants of
+/// `Enum!()`s or `EnumN`s in the type list are flattened recursively.
+///
+/// For instance, `Flat!( A, Enum!( B, Enum2<C,D> ))` is `Enum4<A,B,C,D>`, and so is
+/// `Flat!( Enum2<A, Enum3<B,C,D>> )`.
+#[proc_macro]
+#[allow( non_snake_case )]
+pub fn Flat( input: TokenStream ) -> TokenStream {
+    let type_list = parse_macro_input!( input as TypeList );
+    let mut types = Vec::new();
+    for ty in type_list.0 {
+        if let Err( err ) = flatten_type( ty, &mut types ) {
+            return err.to_compile_error().into();
+        }
+    }
+    predefined_enum( types ).into()
+}
+
+/// `Union!( Type0, Type1, ..., TypeM )` denotes a predefined enum composed of the variants of all the `Enum!()`s or
+/// `EnumN`s in the type list, with duplicated types merged. Types other than enums are considered as variants.
+///
+/// For instance, `Union!( Enum!(A,B), Enum!(B,C) )` is `Enum3<A,B,C>`.
+#[proc_macro]
+#[allow( non_snake_case )]
+pub fn Union( input: TokenStream ) -> TokenStream {
+    let type_list = parse_macro_input!( input as TypeList );
+    let mut types = IndexSet::<Type>::new();
+    for ty in type_list.0 {
+        match variant_types( &ty ) {
+            Ok( Some( variants )) => types.extend( variants ),
+            Ok( None ) => { types.insert( ty ); },
+            Err( err ) => return err.to_compile_error().into(),
+        }
+    }
+    predefined_enum( types ).into()
+}
+
+/// `Minus!( Enum, Type0, Type1, ..., TypeM )` denotes a predefined enum composed of the variants of the `Enum!()` or
+/// `EnumN`, excluding the types in the type list. Variants of enums in the type list are excluded as well.
+///
+/// For instance, `Minus!( Enum!(A,B,C), B )` is `Enum2<A,C>`.
+#[proc_macro]
+#[allow( non_snake_case )]
+pub fn Minus( input: TokenStream ) -> TokenStream {
+    let type_list = parse_macro_input!( input as TypeList );
+    let mut iter = type_list.0.into_iter();
+    let base = match iter.next() {
+        Some( base ) => base,
+        None => return syn::Error::new( Span::call_site(), "expect an enum to subtract from" ).to_compile_error().into(),
+    };
+    let mut types = match variant_types( &base ) {
+        Ok( Some( variants )) => IndexSet::<Type>::from_iter( variants ),
+        Ok( None ) => return syn::Error::new( base.span(), "expect `Enum!()` or `EnumN<..>`" ).to_compile_error().into(),
+        Err( err ) => return err.to_compile_error().into(),
+    };
+    for ty in iter {
+        match variant_types( &ty ) {
+            Ok( Some( variants )) => variants.iter().for_each( |variant| { types.shift_remove( variant ); }),
+            Ok( None ) => { types.shift_remove( &ty ); },
+            Err( err ) => return err.to_compile_error().into(),
+        }
+    }
+    predefined_enum( types ).into()
+}
+
+fn predefined_enum( types: impl IntoIterator<Item=Type> ) -> proc_macro2::TokenStream {
+    let types = types.into_iter().collect::<Vec<_>>();
+    if types.len() > MAX_PREDEFINED {
+        return coproduct( types );
+    }
+    let name = make_ident( &format!( "Enum{}", types.len() ));
+    if types.is_empty() {
+        quote!( #name )
+    } else {
+        quote!( #name::<#(#types),*> )
+    }
+}
+
+fn flatten_type( ty: Type, flat: &mut Vec<Type> ) -> syn::Result<()> {
+    match variant_types( &ty )? {
+        Some( variants ) => for variant in variants {
+            flatten_type( variant, flat )?;
+        },
+        None => flat.push( ty ),
+    }
+    Ok(())
+}
+
+/// Returns the variant types if the type is `Enum!()` or a predefined `EnumN`.
+fn variant_types( ty: &Type ) -> syn::Result<Option<Vec<Type>>> {
+    match ty {
+        Type::Macro( TypeMacro{ mac }) if path_ident_name( &mac.path ).as_deref() == Some( "Enum" ) => {
+            Ok( Some( mac.parse_body::<TypeList>()?.0.into_iter().collect() ))
+        },
+        Type::Path( TypePath{ qself: None, path }) if predefined_enum_arity( path ).is_some() => {
+            let mut variants = Vec::new();
+            if let PathArguments::AngleBracketed( args ) = &path.segments.last().unwrap().arguments {
+                for arg in &args.args {
+                    match arg {
+                        syn::GenericArgument::Type( ty ) => variants.push( ty.clone() ),
+                        _ => return Err( syn::Error::new( arg.span(), "expect type arguments only" )),
+                    }
+                }
+            }
+            Ok( Some( variants ))
+        },
+        Type::Group( type_group ) => variant_types( &type_group.elem ),
+        Type::Paren( type_paren ) => variant_types( &type_paren.elem ),
+        _ => Ok( None ),
+    }
+}
+
+/// Returns N if the path denotes a predefined `EnumN` with N type arguments.
+fn predefined_enum_arity( path: &Path ) -> Option<usize> {
+    let segment = path.segments.last()?;
+    let arity = segment.ident.to_string().strip_prefix( "Enum" )?.parse::<usize>().ok()?;
+    let args = match &segment.arguments {
+        PathArguments::None => 0,
+        PathArguments::AngleBracketed( args ) => args.args.len(),
+        PathArguments::Parenthesized(_) => return None,
+    };
+    if args == arity { Some( arity )} else { None }
+}
+
+/// The maximum count of variants of predefined enums, which is decided by the crate
+/// features of enumx, forwarded to this crate.
+const MAX_PREDEFINED: usize = if cfg!( feature="enum32" ) {
+    32
+} else if cfg!( feature="enum16" ) {
+    16
+} else {
+    0
+};
+
+/// `Coprod!( Type0, Type1, ..., TypeM )` denotes a coproduct of unlimited variants:
+///
+/// ```text
+/// enumx::coproduct::Coprod<Type0, enumx::coproduct::Coprod<Type1, ... enumx::coproduct::CNil>>
+/// ```
+#[proc_macro]
+#[allow( non_snake_case )]
+pub fn Coprod( input: TokenStream ) -> TokenStream {
+    let type_list = parse_macro_input!( input as TypeList );
+    coproduct( type_list.0 ).into()
+}
+
+fn coproduct( types: impl IntoIterator<Item=Type> ) -> proc_macro2::TokenStream {
+    let types = types.into_iter().collect::<Vec<_>>();
+    types.iter().rev().fold( quote!( enumx::coproduct::CNil ), |tail, head| {
+        quote!( enumx::coproduct::Coprod<#head, #tail> )
+    })
+}
+
 struct TypeList( Punctuated<Type, Token![,]> );
 
 impl Parse for TypeList {
@@ -153,7 +362,7 @@ impl Parse for EnumDefImpls {
             let def = input.parse::<ItemMacro>()?;
             match path_ident_name( &def.mac.path ).as_deref() {
                 Some( "_def" ) => (),
-                _ => panic!( "{}", "expect _def!{{}}" ),
+                _ => return Err( syn::Error::new_spanned( &def.mac.path, "expect _def!{}" )),
             }
             let item_enum = def.mac.parse_body::<ItemEnum>()?;
 
@@ -194,8 +403,9 @@ impl Parse for EnumDefImpls {
 
             let content;
             bracketed!( content in input );
-            let range = parse_range( content.parse::<ExprRange>()? )
-                .expect("ranges should be expressed in literial integers");
+            let expr_range = content.parse::<ExprRange>()?;
+            let range = parse_range( expr_range.clone() ).ok_or_else( ||
+                syn::Error::new_spanned( &expr_range, "ranges should be expressed in literial integers" ))?;
 
             let where_clause = if input.peek( Token![where] ) {
                 Some( input.parse::<WhereClause>()? )
@@ -326,7 +536,9 @@ fn parse_range( expr_range: ExprRange ) -> Option<Range<usize>> {
 /// ```
 #[proc_macro]
 pub fn def_impls( input: TokenStream ) -> TokenStream {
-    match parse_macro_input!( input as EnumDefImpls ) {
+    let mut errors = Errors::default();
+
+    let mut expanded = match parse_macro_input!( input as EnumDefImpls ) {
         EnumDefImpls( EnumDef::Ranged( RangedEnums{ attrs, vis, ident, range, where_clause }), item_impls ) => {
             let vnames = (0..range.end).map( |i| make_ident( &format!( "_{}", i )));
             let vtypes = (0..range.end).map( |i| make_ident( &format!( "_T{}", i )));
@@ -361,13 +573,18 @@ pub fn def_impls( input: TokenStream ) -> TokenStream {
 
                     expanded_where_clause = where_clause.clone();
 
-                    EnumWhere::expand(
+                    if let Err( error ) = EnumWhere::expand(
                         &mut expanded_where_clause,
                         vtypes.clone().map( |ident| {
                             let ty: Type = parse_quote!( #ident );
                             ty
                         }).collect::<Vec<_>>()
-                    );
+                    ) {
+                        // reported once for all the enums in range
+                        if errors.is_empty() {
+                            errors.push( error );
+                        }
+                    }
                 }
 
                 enums.push( parse_quote! {
@@ -376,7 +593,147 @@ pub fn def_impls( input: TokenStream ) -> TokenStream {
                     }
                 });
 
+                if where_clause.is_none() {
+                    let vnames = vnames.clone().take( index ).collect::<Vec<_>>();
+                    let vtypes = vtypes.clone().take( index );
+                    let ref_generics = vtypes.clone().map( |vtype| quote!( &#vtype ));
+                    let mut_generics = vtypes.clone().map( |vtype| quote!( &mut #vtype ));
+                    let ( ref_type, mut_type ): ( Type, Type ) = if index == 0 {
+                        ( parse_quote!( #ident ), parse_quote!( #ident ))
+                    } else {
+                        ( parse_quote!( #ident<#(#ref_generics),*> ), parse_quote!( #ident<#(#mut_generics),*> ))
+                    };
+
+                    impls.push( parse_quote! {
+                        impl #generics #ident #generics {
+                            /// Borrows the variant, keeping its position in the enum.
+                            pub fn as_variants_ref( &self ) -> #ref_type {
+                                match *self {
+                                    #( #ident::#vnames( ref v ) => #ident::#vnames( v ), )*
+                                }
+                            }
+
+                            /// Mutably borrows the variant, keeping its position in the enum.
+                            pub fn as_variants_mut( &mut self ) -> #mut_type {
+                                match *self {
+                                    #( #ident::#vnames( ref mut v ) => #ident::#vnames( v ), )*
+                                }
+                            }
+                        }
+                    });
+                }
+
+                if is_proto {
+                    impls.push( parse_quote! {
+                        impl #generics enumx::Proto for enumx::proto::#ident #generics {
+                            type Type = Self;
+                            fn from_proto( src: Self ) -> Self { src }
+                            fn into_proto( self ) -> Self { self }
+                        }
+                    });
+                }
+
+                if is_proto {
+                    let ref_types = vtypes.clone().take( index ).map( |vtype| quote!( &'__enumx #vtype ));
+                    let ref_type: Type = if index == 0 {
+                        parse_quote!( enumx::proto::#ident )
+                    } else {
+                        parse_quote!( enumx::proto::#ident<#(#ref_types),*> )
+                    };
+                    let mut_types = vtypes.clone().take( index ).map( |vtype| quote!( &'__enumx mut #vtype ));
+                    let mut_type: Type = if index == 0 {
+                        parse_quote!( enumx::proto::#ident )
+                    } else {
+                        parse_quote!( enumx::proto::#ident<#(#mut_types),*> )
+                    };
+                    let ntypes = vtypes.clone().take( index );
+                    let ntypes_ = ntypes.clone();
+
+                    impls.push( parse_quote! {
+                        impl<'__enumx, #(#ntypes),*> enumx::AsProto<'__enumx> for enumx::proto::#ident #generics
+                            where Self: '__enumx
+                        {
+                            type Type = #ref_type;
+                            fn as_proto( &'__enumx self ) -> #ref_type { self.as_variants_ref() }
+                        }
+                    });
+
+                    impls.push( parse_quote! {
+                        impl<'__enumx, #(#ntypes_),*> enumx::AsMutProto<'__enumx> for enumx::proto::#ident #generics
+                            where Self: '__enumx
+                        {
+                            type Type = #mut_type;
+                            fn as_mut_proto( &'__enumx mut self ) -> #mut_type { self.as_variants_mut() }
+                        }
+                    });
+
+                    impls.extend( serde_proto_impls( &ident, index ));
+                }
+
+                if is_proto && index == 0 {
+                    impls.push( parse_quote! {
+                        impl<Src, Dest> enumx::ExchangeFrom<Src, enumx::EnumToEnum<()>> for Dest
+                            where Src: enumx::Proto<Type=enumx::proto::#ident>
+                        {
+                            fn exchange_from( src: Src ) -> Dest {
+                                match src.into_proto() {}
+                            }
+                        }
+                    });
+                }
+
+                if is_proto {
+                    let ntypes = vtypes.clone().take( index ).collect::<Vec<_>>();
+                    let nidxes = itypes.clone().take( index ).collect::<Vec<_>>();
+                    let rtypes = (1..=index).map( |i| make_ident( &format!( "_R{}", i ))).collect::<Vec<_>>();
+                    let srcs = (0..index).map( |i| -> Type {
+                        if i == 0 {
+                            parse_quote!( Src )
+                        } else {
+                            let rtype = &rtypes[ i-1 ];
+                            parse_quote!( #rtype )
+                        }
+                    }).collect::<Vec<_>>();
+                    let remainder: Type = match rtypes.last() {
+                        Some( rtype ) => parse_quote!( #rtype ),
+                        None          => parse_quote!( Src ),
+                    };
+                    let proto_names = vnames.clone().take( index );
+
+                    impls.push( parse_quote! {
+                        impl<#(#nidxes,)* #(#ntypes,)* #(#rtypes,)* Src, Dest> enumx::TryExchangeFrom<Src, enumx::EnumToEnum<(#(#nidxes,)*)>> for Dest
+                            where Dest : enumx::Proto<Type=enumx::proto::#ident #generics>
+                                #(, #srcs : enumx::Narrow<#ntypes, #nidxes, Remainder=#rtypes> )*
+                        {
+                            type Remainder = #remainder;
+
+                            fn try_exchange_from( src: Src ) -> ::core::result::Result<Dest, #remainder> {
+                                let rest = src;
+                                #(
+                                    let rest = match <#srcs as enumx::Narrow<#ntypes, #nidxes>>::narrow( rest ) {
+                                        ::core::result::Result::Ok(v) => return ::core::result::Result::Ok(
+                                            Dest::from_proto( enumx::proto::#ident::#proto_names(v) )),
+                                        ::core::result::Result::Err( rest ) => rest,
+                                    };
+                                )*
+                                ::core::result::Result::Err( rest )
+                            }
+                        }
+                    });
+                }
+
                 if is_proto && index != 0 {
+                    let name: Path = parse_quote!( enumx::proto::#ident );
+                    let generics = generics.as_ref().unwrap();
+                    let proto_variants = vnames.clone().zip( vtypes.clone() ).take( index )
+                        .map( |(vname, vtype)| ExchangeVariant::newtype( vname, parse_quote!( #vtype )))
+                        .collect::<Vec<_>>();
+
+                    impls.extend( narrow_impls( &name, generics, &proto_variants ));
+                    impls.extend( get_variant_impls( &name, generics, &proto_variants ));
+                    impls.extend( map_variant_impls( &name, generics, &proto_variants ));
+                    impls.push( accept_impl( &name, generics, &proto_variants ));
+
                     impls.push( parse_quote! {
                         impl<#(#itypes1,)* #(#vtypes3,)* Src, Dest> enumx::ExchangeFrom<Src, enumx::EnumToEnum<(#(#itypes2,)*)>> for Dest
                             where Src  : enumx::Proto<Type=enumx::proto::#ident #generics>
@@ -392,155 +749,723 @@ pub fn def_impls( input: TokenStream ) -> TokenStream {
                 }
             }
 
-            let mut expanded = TokenStream::from( quote!( #( #enums )* #( #impls )* ));
+            let mut expanded = quote!( #( #enums )* #( #impls )* );
             for item_impl in item_impls {
-                Extend::<TokenTree>::extend( &mut expanded, expand_enum_impl( item_impl, None ));
+                match expand_enum_impl( item_impl, None ) {
+                    Ok( expanded_impl ) => expanded.extend( expanded_impl ),
+                    Err( error ) => errors.push( error ),
+                }
             }
-            expanded.into()
+            expanded
         },
         EnumDefImpls( EnumDef::Single( item_enum ), item_impls ) => {
-            let mut expanded_impls = TokenStream::new();
+            let mut expanded = quote!( #item_enum );
             for item_impl in item_impls {
-                expanded_impls.extend( expand_enum_impl( item_impl, Some( &item_enum )));
+                match expand_enum_impl( item_impl, Some( &item_enum )) {
+                    Ok( expanded_impl ) => expanded.extend( expanded_impl ),
+                    Err( error ) => errors.push( error ),
+                }
             }
-
-            let mut expanded_item_enum = TokenStream::from( quote!( #item_enum ));
-            Extend::<TokenTree>::extend( &mut expanded_item_enum, expanded_impls );
-            expanded_item_enum.into()
+            expanded
         },
         EnumDefImpls( EnumDef::Snapshot( item_enum ), item_impls ) => {
-            let mut expanded_impls = TokenStream::new();
+            let mut expanded = proc_macro2::TokenStream::new();
             for item_impl in item_impls {
-                expanded_impls.extend( expand_enum_impl( item_impl, Some( &item_enum )));
+                match expand_enum_impl( item_impl, Some( &item_enum )) {
+                    Ok( expanded_impl ) => expanded.extend( expanded_impl ),
+                    Err( error ) => errors.push( error ),
+                }
             }
-            expanded_impls
+            expanded
         },
         EnumDefImpls( EnumDef::None, item_impls ) => {
-            let mut expanded_impls = TokenStream::new();
+            let mut expanded = proc_macro2::TokenStream::new();
             for item_impl in item_impls {
-                expanded_impls.extend( expand_enum_impl( item_impl, None ));
+                match expand_enum_impl( item_impl, None ) {
+                    Ok( expanded_impl ) => expanded.extend( expanded_impl ),
+                    Err( error ) => errors.push( error ),
+                }
             }
-            expanded_impls
+            expanded
         },
-    }
+    };
+
+    expanded.extend( errors.to_compile_error() );
+    expanded.into()
 }
 
-/// Derives the trait `enumx::FromVariant` for user defined enum types.
-#[proc_macro_derive( FromVariant )]
-pub fn derive_from_variant( input: TokenStream ) -> TokenStream {
-    let input: DeriveInput = syn::parse( input ).unwrap();
+/// Implements `enumx::encoding::{SerializeProto, DeserializeProto}` for a prototype enum, if the "serde" feature of
+/// enumx is enabled.
+fn serde_proto_impls( ident: &Ident, index: usize ) -> Vec<ItemImpl> {
+    let name = ident.to_string();
+    let vnames = (0..index).map( |i| make_ident( &format!( "_{}", i ))).collect::<Vec<_>>();
+    let vtypes = (0..index).map( |i| make_ident( &format!( "_T{}", i ))).collect::<Vec<_>>();
+    let variants = vnames.iter().map( |vname| vname.to_string() ).collect::<Vec<_>>();
+    let indices = (0..index).map( |i| i as u32 ).collect::<Vec<_>>();
+
+    let ( ty, ref_ty ): ( Type, Type ) = if index == 0 {
+        ( parse_quote!( enumx::proto::#ident ), parse_quote!( enumx::proto::#ident ))
+    } else {
+        ( parse_quote!( enumx::proto::#ident<#(#vtypes),*> ), parse_quote!( enumx::proto::#ident<#(&'__enumx #vtypes),*> ))
+    };
 
-    match input.data {
-        syn::Data::Enum( ref data ) => {
-            if data.variants.len() == 0 {
-                return TokenStream::new();
+    vec![
+        parse_quote! {
+            #[cfg( feature="serde" )]
+            impl<'__enumx, #(#vtypes),*> enumx::encoding::SerializeProto for #ref_ty
+                where #( #vtypes: serde::Serialize, )* Self: '__enumx
+            {
+                fn serialize_proto<S: serde::Serializer>( &self, serializer: S, tagging: enumx::encoding::Tagging )
+                    -> ::core::result::Result<S::Ok, S::Error>
+                {
+                    match *self {
+                        #( enumx::proto::#ident::#vnames(v) => enumx::encoding::serialize_variant(
+                            serializer, tagging, #name, #indices, #variants, v ), )*
+                    }
+                }
             }
+        },
+        parse_quote! {
+            #[cfg( feature="serde" )]
+            impl<'de, #(#vtypes),*> enumx::encoding::DeserializeProto<'de> for #ty
+                where #( #vtypes: serde::Deserialize<'de>, )* Self: Sized
+            {
+                const NAME: &'static str = #name;
+                const VARIANTS: &'static [&'static str] = &[ #(#variants),* ];
+
+                fn type_index( type_name: &str ) -> ::core::option::Option<u32> {
+                    #(
+                        if type_name == ::core::any::type_name::<#vtypes>() {
+                            return ::core::option::Option::Some( #indices );
+                        }
+                    )*
+                    ::core::option::Option::None
+                }
 
-            let name = &input.ident;
+                fn deserialize_variant<A: serde::de::VariantAccess<'de>>( index: u32, variant: A )
+                    -> ::core::result::Result<Self, A::Error>
+                {
+                    match index {
+                        #( #indices => variant.newtype_variant::<#vtypes>().map( enumx::proto::#ident::#vnames ), )*
+                        _ => ::core::result::Result::Err( serde::de::Error::custom(
+                            ::core::format_args!( "variant index {} out of range of {}", index, #name ))),
+                    }
+                }
 
-            let variant_names = data.variants.iter().map( |v| &v.ident );
+                fn deserialize_untagged<E: serde::de::Error>( value: serde_value::Value ) -> ::core::result::Result<Self, E> {
+                    #(
+                        if let ::core::result::Result::Ok(v) = value.clone().deserialize_into::<#vtypes>() {
+                            return ::core::result::Result::Ok( enumx::proto::#ident::#vnames(v) );
+                        }
+                    )*
+                    let _ = value;
+                    ::core::result::Result::Err( serde::de::Error::custom(
+                        ::core::concat!( "data did not match any variant of enum ", #name )))
+                }
+            }
+        },
+    ]
+}
 
-            let (ref impl_generics, ref ty_generics, ref where_clause) = input.generics.split_for_impl();
+/// The `#[exchange(...)]` attribute on a variant of a user defined enum.
+#[derive( Default )]
+struct ExchangeAttr {
+    tag  : Option<Type>,
+    skip : bool,
+}
 
-            let variant_types = data.variants.iter().map( |ref v| {
-                if let syn::Fields::Unnamed( ref fields ) = v.fields {
-                    let mut iter = fields.unnamed.iter();
-                    if iter.len() == 1 {
-                        let field = iter.next().unwrap();
-                        return &field.ty;
-                    }
-                }
-                syntax_error!();
-            });
-            let variant_types = variant_types.clone();
+impl Parse for ExchangeAttr {
+    fn parse( input: ParseStream ) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        if ident == "skip" {
+            Ok( ExchangeAttr{ tag: None, skip: true })
+        } else if ident == "tag" {
+            input.parse::<Token![=]>()?;
+            Ok( ExchangeAttr{ tag: Some( input.parse::<Type>()? ), skip: false })
+        } else {
+            Err( syn::Error::new( ident.span(), "#[exchange( tag = Type )] or #[exchange( skip )] expected" ))
+        }
+    }
+}
 
-            let mut impls = Vec::<ItemImpl>::new();
-            let mut indices = Vec::<Ident>::new();
-
-            for (i, (vname,vtype) ) in variant_names.zip( variant_types ).enumerate() {
-                let v: Type = parse_quote!( [(); #i] );
-                indices.push( make_ident( &format!( "_I{}", i )));
-                impls.push( parse_quote! {
-                    impl #impl_generics enumx::FromVariant<#vtype,#v> for #name #ty_generics #where_clause {
-                        fn from_variant( src: #vtype ) -> Self { #name::#vname( src )}
-                    }
-                });
+/// A variant of a user defined enum, the fields of which are exchanged as one value of the variant type:
+/// `()` for a variant without fields, the field itself for a variant of one field, or the tuple of fields.
+/// A tagged variant wraps the value in `enumx::Tagged`.
+#[derive( Clone )]
+struct ExchangeVariant {
+    ident   : Ident,
+    members : Vec<Member>,
+    types   : Vec<Type>,
+    tag     : Option<Type>,
+    skip    : bool,
+}
+
+impl ExchangeVariant {
+    fn from_variant( variant: &Variant ) -> syn::Result<Self> {
+        let members = variant.fields.iter().enumerate().map( |(index, field)| match &field.ident {
+            Some( ident ) => Member::Named( ident.clone() ),
+            None => Member::Unnamed( Index::from( index )),
+        }).collect();
+        let types = variant.fields.iter().map( |field| field.ty.clone() ).collect();
+
+        let mut errors = Errors::default();
+        let mut attr = ExchangeAttr::default();
+        for attribute in variant.attrs.iter().filter( |attribute| attribute.path.is_ident( "exchange" )) {
+            match attribute.parse_args::<ExchangeAttr>() {
+                Ok( ExchangeAttr{ tag: Some( tag ), .. }) => attr.tag = Some( tag ),
+                Ok( ExchangeAttr{ skip, .. }) => attr.skip |= skip,
+                Err( error ) => errors.push( error ),
             }
+        }
+        if attr.skip && attr.tag.is_some() {
+            errors.error( &variant.ident, format!( "#[exchange( skip )] variant `{}` should not be tagged", variant.ident ));
+        }
+        errors.into_result()?;
 
-            let impls = impls.iter();
+        Ok( ExchangeVariant{ ident: variant.ident.clone(), members, types, tag: attr.tag, skip: attr.skip })
+    }
 
-            let expanded = quote!( #(#impls)* );
-            expanded.into()
-        },
-        _ => panic!( "Only `enum`s can be constructed `FromVariant`s." ),
+    fn from_enum( data: &DataEnum ) -> syn::Result<Vec<Self>> {
+        let mut errors = Errors::default();
+        let mut variants = Vec::new();
+        for variant in &data.variants {
+            match ExchangeVariant::from_variant( variant ) {
+                Ok( variant ) => variants.push( variant ),
+                Err( error ) => errors.push( error ),
+            }
+        }
+        errors.into_result().map( |_| variants )
+    }
+
+    fn newtype( ident: Ident, ty: Type ) -> Self {
+        ExchangeVariant{ ident, members: vec![ Member::Unnamed( Index::from( 0 ))], types: vec![ ty ], tag: None, skip: false }
+    }
+
+    fn is_newtype( &self ) -> bool {
+        self.members.len() == 1
+    }
+
+    /// Wraps the type of fields in `enumx::Tagged` if the variant is tagged.
+    fn tagged_ty( &self, ty: Type ) -> Type {
+        match &self.tag {
+            Some( tag ) => parse_quote!( enumx::Tagged<#tag, #ty> ),
+            None => ty,
+        }
+    }
+
+    /// Wraps the value of fields in `enumx::Tagged` if the variant is tagged.
+    fn tagged_value( &self, value: proc_macro2::TokenStream ) -> proc_macro2::TokenStream {
+        match &self.tag {
+            Some(_) => quote!( enumx::Tagged::new( #value )),
+            None => value,
+        }
+    }
+
+    fn bindings( &self ) -> Vec<Ident> {
+        if self.is_newtype() {
+            vec![ make_ident( "v" )]
+        } else {
+            (0..self.members.len()).map( |index| make_ident( &format!( "v{}", index ))).collect()
+        }
+    }
+
+    /// The variant type.
+    fn ty( &self ) -> Type {
+        let types = &self.types;
+        if self.is_newtype() {
+            self.tagged_ty( types[0].clone() )
+        } else {
+            self.tagged_ty( parse_quote!( (#(#types),*) ))
+        }
+    }
+
+    /// The type of the borrowed variant.
+    fn ref_ty( &self, lifetime: &Lifetime ) -> Type {
+        let types = &self.types;
+        match types.len() {
+            0 => { let ty = self.tagged_ty( parse_quote!( () )); parse_quote!( &#lifetime #ty )},
+            1 => { let ty = self.ty(); parse_quote!( &#lifetime #ty )},
+            _ => self.tagged_ty( parse_quote!( (#(&#lifetime #types),*) )),
+        }
+    }
+
+    /// The type of the mutably borrowed variant, in which a variant without fields is `()`.
+    fn mut_ty( &self, lifetime: &Lifetime ) -> Type {
+        let types = &self.types;
+        match types.len() {
+            1 => { let ty = self.ty(); parse_quote!( &#lifetime mut #ty )},
+            _ => self.tagged_ty( parse_quote!( (#(&#lifetime mut #types),*) )),
+        }
     }
+
+    /// The pattern binding the fields, which is also the expression constructing the variant from them.
+    fn pat( &self, name: &Path ) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let members = &self.members;
+        let bindings = self.bindings();
+        quote!( #name::#ident{ #( #members: #bindings ),* } )
+    }
+
+    /// The pattern binding the fields by reference.
+    fn ref_pat( &self, name: &Path ) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let members = &self.members;
+        let bindings = self.bindings();
+        quote!( #name::#ident{ #( #members: ref #bindings ),* } )
+    }
+
+    /// The pattern binding the fields by mutable reference.
+    fn mut_pat( &self, name: &Path ) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let members = &self.members;
+        let bindings = self.bindings();
+        quote!( #name::#ident{ #( #members: ref mut #bindings ),* } )
+    }
+
+    /// The pattern ignoring the fields.
+    fn wild_pat( &self, name: &Path ) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        quote!( #name::#ident{ .. } )
+    }
+
+    /// The value of the variant type, from the fields bound by `pat()`.
+    fn value( &self ) -> proc_macro2::TokenStream {
+        let bindings = self.bindings();
+        if self.is_newtype() {
+            self.tagged_value( quote!( #(#bindings)* ))
+        } else {
+            self.tagged_value( quote!( (#(#bindings),*) ))
+        }
+    }
+
+    /// The value of the borrowed variant type, from the fields bound by `pat()` in a borrowed enum.
+    fn ref_value( &self ) -> proc_macro2::TokenStream {
+        match self.members.len() {
+            0 => self.tagged_ref( quote!( &() )),
+            1 => { let field = &self.bindings()[0]; self.tagged_ref( quote!( #field ))},
+            _ => self.value(),
+        }
+    }
+
+    /// The value of the mutably borrowed variant type, from the fields bound by `mut_pat()`.
+    fn mut_value( &self ) -> proc_macro2::TokenStream {
+        match self.members.len() {
+            1 => { let field = &self.bindings()[0]; self.tagged_mut( quote!( #field ))},
+            _ => self.value(),
+        }
+    }
+
+    /// Wraps the reference of the field in a reference of `enumx::Tagged` if the variant is tagged.
+    fn tagged_ref( &self, field: proc_macro2::TokenStream ) -> proc_macro2::TokenStream {
+        match &self.tag {
+            Some(_) => quote!( enumx::Tagged::from_ref( #field )),
+            None => field,
+        }
+    }
+
+    /// Wraps the mutable reference of the field in a mutable reference of `enumx::Tagged` if the variant is tagged.
+    fn tagged_mut( &self, field: proc_macro2::TokenStream ) -> proc_macro2::TokenStream {
+        match &self.tag {
+            Some(_) => quote!( enumx::Tagged::from_mut( #field )),
+            None => field,
+        }
+    }
+
+    /// Constructs the variant from the value of the variant type.
+    fn construct( &self, name: &Path, value: proc_macro2::TokenStream ) -> proc_macro2::TokenStream {
+        let value = match &self.tag {
+            Some(_) => quote!( enumx::Tagged::into_inner( #value )),
+            None => value,
+        };
+        if self.is_newtype() {
+            let ident = &self.ident;
+            let member = &self.members[0];
+            quote!( #name::#ident{ #member: #value } )
+        } else {
+            let bindings = self.bindings();
+            let pat = self.pat( name );
+            quote!({ let (#(#bindings),*) = #value; #pat })
+        }
+    }
+}
+
+/// Returns the variants of the user defined enum to be exchanged, or reports the other data type with the message.
+fn exchange_variants( input: &DeriveInput, message: &str ) -> syn::Result<Vec<ExchangeVariant>> {
+    match &input.data {
+        syn::Data::Enum( data ) => ExchangeVariant::from_enum( data ),
+        syn::Data::Struct( data ) => Err( syn::Error::new( data.struct_token.span, message )),
+        syn::Data::Union( data ) => Err( syn::Error::new( data.union_token.span, message )),
+    }
+}
+
+/// Derives the trait `enumx::FromVariant` for user defined enum types.
+#[proc_macro_derive( FromVariant, attributes( exchange ))]
+pub fn derive_from_variant( input: TokenStream ) -> TokenStream {
+    let input = parse_macro_input!( input as DeriveInput );
+
+    exchange_variants( &input, "Only `enum`s can be constructed `FromVariant`s." )
+        .map( |variants| from_variant_impls( &input, &variants ))
+        .unwrap_or_else( |error| error.to_compile_error() )
+        .into()
+}
+
+fn from_variant_impls( input: &DeriveInput, variants: &[ExchangeVariant] ) -> proc_macro2::TokenStream {
+    let name = &input.ident;
+    let name_path: Path = parse_quote!( #name );
+
+    let (ref impl_generics, ref ty_generics, ref where_clause) = input.generics.split_for_impl();
+
+    let mut impls = Vec::<ItemImpl>::new();
+
+    for (i, variant) in variants.iter().enumerate().filter( |(_,variant)| !variant.skip ) {
+        let v: Type = parse_quote!( [(); #i] );
+        let vtype = variant.ty();
+        let construct = variant.construct( &name_path, quote!( src ));
+        impls.push( parse_quote! {
+            impl #impl_generics enumx::FromVariant<#vtype,#v> for #name #ty_generics #where_clause {
+                fn from_variant( src: #vtype ) -> Self { #construct }
+            }
+        });
+    }
+
+    let impls = impls.iter();
+
+    quote!( #(#impls)* )
 }
 
 /// Since `enum`s in Rust do not have prototypes, this macro derives them.
-#[proc_macro_derive( Proto )]
+#[proc_macro_derive( Proto, attributes( exchange ))]
 pub fn derive_proto( input: TokenStream ) -> TokenStream {
-    let input: DeriveInput = syn::parse( input ).unwrap();
+    let input = parse_macro_input!( input as DeriveInput );
 
-    match input.data {
-        syn::Data::Enum( ref data ) => {
-            let name = &input.ident;
+    exchange_variants( &input, "Only `enum`s can have `Proto`-type." )
+        .and_then( |variants| proto_impls( &input, &variants ))
+        .unwrap_or_else( |error| error.to_compile_error() )
+        .into()
+}
+
+fn proto_impls( input: &DeriveInput, variants: &[ExchangeVariant] ) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let name_path: Path = parse_quote!( #name );
+
+    let mut errors = Errors::default();
+    for variant in variants.iter().filter( |variant| variant.skip ) {
+        errors.error( &variant.ident, format!( "`Proto` is not derivable for the #[exchange( skip )] variant `{}`.", variant.ident ));
+    }
+    errors.into_result()?;
+
+    let variant_cnt = variants.len();
+
+    let protox = &make_ident( &format!( "__{}", variant_cnt ));
+    let proto_names = (0..variant_cnt).map( |index| make_ident( &format!( "_{}", index ))).collect::<Vec<_>>();
+
+    let pats = variants.iter().map( |variant| variant.pat( &name_path ));
+    let ref_pats = variants.iter().map( |variant| variant.ref_pat( &name_path ));
+    let mut_pats = variants.iter().map( |variant| variant.mut_pat( &name_path ));
+    let values = variants.iter().map( ExchangeVariant::value );
+    let ref_values = variants.iter().map( ExchangeVariant::ref_value );
+    let mut_values = variants.iter().map( ExchangeVariant::mut_value );
+    let constructs = variants.iter().map( |variant| variant.construct( &name_path, quote!( v )));
+
+    let ( ref impl_generics, ref ty_generics, ref where_clause ) = input.generics.split_for_impl();
+
+    let variant_ty = variants.iter().map( ExchangeVariant::ty ).collect::<Vec<_>>();
+
+    let lifetime: Lifetime = parse_quote!( '__proto );
+    let ref_variant_ty = variants.iter().map( |variant| variant.ref_ty( &lifetime ));
+    let ref_enum_ty: syn::Type = parse_quote!{ enumx::proto::#protox<#(#ref_variant_ty),*> };
+    let mut_variant_ty = variants.iter().map( |variant| variant.mut_ty( &lifetime ));
+    let mut_enum_ty: syn::Type = parse_quote!{ enumx::proto::#protox<#(#mut_variant_ty),*> };
+
+    let mut ref_generics = input.generics.clone();
+    ref_generics.params.insert( 0, parse_quote!( '__proto ));
+    {
+        let where_clause = ref_generics.make_where_clause();
+        variant_ty.iter().for_each( |ty| where_clause.predicates.push( parse_quote!( #ty: '__proto )));
+    }
+    let ( ref_impl_generics, _, ref_where_clause ) = ref_generics.split_for_impl();
+
+    let enumx_ty: syn::Type = parse_quote!{ enumx::proto::#protox<#(#variant_ty),*> };
 
-            let named_variant_fp = data.variants.iter().map( |v| &v.ident );
-            let named_variant_tp = data.variants.iter().map( |v| &v.ident );
+    Ok( quote! {
+        impl #ref_impl_generics enumx::AsProto<'__proto> for #name #ty_generics #ref_where_clause {
+            type Type = #ref_enum_ty;
 
-            let variant_cnt = data.variants.len();
+            fn as_proto( &'__proto self ) -> #ref_enum_ty {
+                match *self {
+                    #( #ref_pats => enumx::proto::#protox::#proto_names( #ref_values ), )*
+                }
+            }
+        }
+
+        impl #ref_impl_generics enumx::AsMutProto<'__proto> for #name #ty_generics #ref_where_clause {
+            type Type = #mut_enum_ty;
 
-            let protox = &make_ident( &format!( "__{}", variant_cnt ));
+            fn as_mut_proto( &'__proto mut self ) -> #mut_enum_ty {
+                match *self {
+                    #( #mut_pats => enumx::proto::#protox::#proto_names( #mut_values ), )*
+                }
+            }
+        }
 
-            let named_enum_fp = (0..variant_cnt).map( |_| name );
-            let named_enum_tp = named_enum_fp.clone();
+        impl #impl_generics enumx::Proto for #name #ty_generics #where_clause {
+            type Type = #enumx_ty;
 
-            let unamed_enum_fp = (0..variant_cnt).map( |_| protox );
-            let unamed_enum_tp = unamed_enum_fp.clone();
+            fn from_proto( src: #enumx_ty ) -> Self {
+                match src {
+                    #( enumx::proto::#protox::#proto_names(v) => #constructs, )*
+                }
+            }
+
+            fn into_proto( self ) -> #enumx_ty {
+                match self {
+                    #( #pats => enumx::proto::#protox::#proto_names( #values ), )*
+                }
+            }
+        }
+    })
+}
 
-            let unamed_variant_fp = (0..variant_cnt).map( |index| make_ident( &format!( "_{}", index )));
-            let unamed_variant_tp = unamed_variant_fp.clone();
+/// Implements `enumx::Narrow` for each variant of an enum, the remainder of which is a prototype enum.
+fn narrow_impls( name: &Path, generics: &Generics, variants: &[ExchangeVariant] ) -> Vec<ItemImpl> {
+    let ( ref impl_generics, ref ty_generics, ref where_clause ) = generics.split_for_impl();
+    let protox = make_ident( &format!( "__{}", variants.len()-1 ));
+
+    (0..variants.len()).map( |index| {
+        let variant = &variants[ index ];
+        let vtype = variant.ty();
+        let pat = variant.pat( name );
+        let value = variant.value();
+        let itype: Type = parse_quote!( [(); #index] );
+
+        let rest = variants.iter().enumerate().filter( |&(i,_)| i != index ).map( |(_,variant)| variant );
+        let rest_pats = rest.clone().map( |variant| variant.pat( name ));
+        let rest_values = rest.clone().map( ExchangeVariant::value );
+        let rest_types = rest.map( ExchangeVariant::ty );
+        let proto_names = (0..variants.len()-1).map( |i| make_ident( &format!( "_{}", i )));
+
+        let remainder: Type = if variants.len() == 1 {
+            parse_quote!( enumx::proto::#protox )
+        } else {
+            parse_quote!( enumx::proto::#protox<#(#rest_types),*> )
+        };
 
-            let ( ref impl_generics, ref ty_generics, ref where_clause ) = input.generics.split_for_impl();
+        parse_quote! {
+            impl #impl_generics enumx::Narrow<#vtype,#itype> for #name #ty_generics #where_clause {
+                type Remainder = #remainder;
 
-            let variant_ty = data.variants.iter().map( |ref v| {
-                if let syn::Fields::Unnamed( ref fields ) = v.fields {
-                    let mut iter = fields.unnamed.iter();
-                    if iter.len() == 1 {
-                        let field = iter.next().unwrap();
-                        return &field.ty;
+                fn narrow( self ) -> ::core::result::Result<#vtype, #remainder> {
+                    match self {
+                        #pat => ::core::result::Result::Ok( #value ),
+                        #( #rest_pats => ::core::result::Result::Err( enumx::proto::#protox::#proto_names( #rest_values )), )*
                     }
                 }
-                syntax_error!();
-            });
+            }
+        }
+    }).collect()
+}
 
-            let enumx_ty: syn::Type = parse_quote!{ enumx::proto::#protox<#(#variant_ty),*> };
+/// Implements `enumx::Accept` for an enum, which dispatches each variant to `enumx::EnumVisitor`.
+fn accept_impl( name: &Path, generics: &Generics, variants: &[ExchangeVariant] ) -> ItemImpl {
+    let ( _, ref ty_generics, _ ) = generics.split_for_impl();
+
+    let vtypes = variants.iter().map( ExchangeVariant::ty ).collect::<Vec<_>>();
+    let vtype0 = &vtypes[0];
+    let output: Type = parse_quote!( <_Visitor as enumx::EnumVisitor<#vtype0>>::Output );
+
+    let mut accept_generics = generics.clone();
+    accept_generics.params.push( parse_quote!( _Visitor ));
+    {
+        let where_clause = accept_generics.make_where_clause();
+        where_clause.predicates.push( parse_quote!( _Visitor: enumx::EnumVisitor<#vtype0> ));
+        // One predicate for each distinct variant type, otherwise rustc overflows in evaluating `Output`.
+        let mut seen = vec![ quote!( #vtype0 ).to_string() ];
+        for vtype in vtypes.iter().skip(1) {
+            let key = quote!( #vtype ).to_string();
+            if !seen.contains( &key ) {
+                seen.push( key );
+                where_clause.predicates.push( parse_quote!( _Visitor: enumx::EnumVisitor<#vtype, Output=#output> ));
+            }
+        }
+    }
+    let ( ref impl_generics, _, ref where_clause ) = accept_generics.split_for_impl();
 
-            let expanded = quote! {
-                impl #impl_generics enumx::Proto for #name #ty_generics #where_clause {
-                    type Type = #enumx_ty;
+    let pats = variants.iter().map( |variant| variant.pat( name ));
+    let values = variants.iter().map( ExchangeVariant::value );
 
-                    fn from_proto( src: #enumx_ty ) -> Self {
-                        match src {
-                            #( enumx::proto::#unamed_enum_fp::#unamed_variant_fp(v) => #named_enum_fp::#named_variant_fp(v), )*
-                        }
+    parse_quote! {
+        impl #impl_generics enumx::Accept<_Visitor> for #name #ty_generics #where_clause {
+            type Output = #output;
+
+            fn accept( self, visitor: _Visitor ) -> #output {
+                match self {
+                    #( #pats => enumx::EnumVisitor::<#vtypes>::visit( visitor, #values ), )*
+                }
+            }
+        }
+    }
+}
+
+/// Implements `enumx::GetVariant` for each variant of one field, which could be borrowed.
+fn get_variant_impls( name: &Path, generics: &Generics, variants: &[ExchangeVariant] ) -> Vec<ItemImpl> {
+    let ( ref impl_generics, ref ty_generics, ref where_clause ) = generics.split_for_impl();
+
+    (0..variants.len()).filter( |&index| variants[ index ].is_newtype() && !variants[ index ].skip ).map( |index| {
+        let variant = &variants[ index ];
+        let vtype = variant.ty();
+        let pat = variant.pat( name );
+        let ref_value = variant.tagged_ref( quote!(v) );
+        let mut_value = variant.tagged_mut( quote!(v) );
+        let value = variant.value();
+        let itype: Type = parse_quote!( [(); #index] );
+
+        let rest = variants.iter().enumerate().filter( |&(i,_)| i != index ).map( |(_,variant)| variant );
+        let rest_wild_pats = rest.clone().map( |variant| variant.wild_pat( name )).collect::<Vec<_>>();
+        let rest_pats = rest.map( |variant| variant.pat( name )).collect::<Vec<_>>();
+
+        parse_quote! {
+            impl #impl_generics enumx::GetVariant<#vtype,#itype> for #name #ty_generics #where_clause {
+                fn get_variant( &self ) -> ::core::option::Option<&#vtype> {
+                    match self {
+                        #pat => ::core::option::Option::Some( #ref_value ),
+                        #( #rest_wild_pats => ::core::option::Option::None, )*
                     }
+                }
 
-                    fn into_proto( self ) -> #enumx_ty {
-                        match self {
-                            #( #named_enum_tp::#named_variant_tp(v) => enumx::proto::#unamed_enum_tp::#unamed_variant_tp(v), )*
-                        }
+                fn get_variant_mut( &mut self ) -> ::core::option::Option<&mut #vtype> {
+                    match self {
+                        #pat => ::core::option::Option::Some( #mut_value ),
+                        #( #rest_wild_pats => ::core::option::Option::None, )*
                     }
                 }
-            };
-            expanded.into()
-        },
-        _ => panic!( "Only `enum`s can have `Proto`-type." ),
+
+                fn take_variant( self ) -> ::core::result::Result<#vtype, Self> {
+                    match self {
+                        #pat => ::core::result::Result::Ok( #value ),
+                        #( #rest_pats => ::core::result::Result::Err( #rest_pats ), )*
+                    }
+                }
+            }
+        }
+    }).collect()
+}
+
+fn mentions_ident( tokens: proc_macro2::TokenStream, ident: &Ident ) -> bool {
+    tokens.into_iter().any( |tt| match tt {
+        proc_macro2::TokenTree::Ident( i ) => i == *ident,
+        proc_macro2::TokenTree::Group( group ) => mentions_ident( group.stream(), ident ),
+        _ => false,
+    })
+}
+
+/// Returns the type parameter which is the variant type and could be replaced without affecting other variants.
+fn replaceable_type_param<'a>( generics: &'a Generics, vtypes: &[Type], index: usize ) -> Option<&'a Ident> {
+    let ident = match &vtypes[ index ] {
+        Type::Path( TypePath{ qself: None, path }) => path_ident( path )?,
+        _ => return None,
+    };
+
+    let mut type_param = None;
+    for param in &generics.params {
+        match param {
+            GenericParam::Type( param ) if param.ident == ident => {
+                if !param.bounds.is_empty() || param.default.is_some() {
+                    return None;
+                }
+                type_param = Some( &param.ident );
+            },
+            _ => if mentions_ident( quote!( #param ), &ident ) {
+                return None;
+            },
+        }
+    }
+
+    let where_clause = &generics.where_clause;
+    if mentions_ident( quote!( #where_clause ), &ident ) {
+        return None;
     }
+
+    for (i, vtype) in vtypes.iter().enumerate() {
+        if i != index && mentions_ident( quote!( #vtype ), &ident ) {
+            return None;
+        }
+    }
+
+    type_param
+}
+
+/// Implements `enumx::MapVariant` for each variant of an enum.
+///
+/// If the variant type is a type parameter of its own, the output is the enum with the parameter replaced,
+/// otherwise a prototype enum.
+fn map_variant_impls( name: &Path, generics: &Generics, variants: &[ExchangeVariant] ) -> Vec<ItemImpl> {
+    let ( _, ref ty_generics, ref where_clause ) = generics.split_for_impl();
+
+    let mut mapped_generics = generics.clone();
+    add_generics( &mut mapped_generics, parse_quote!( _Mapped ));
+    let ( ref impl_generics, _, _ ) = mapped_generics.split_for_impl();
+
+    let vtypes = variants.iter().map( ExchangeVariant::ty ).collect::<Vec<_>>();
+    let protox = make_ident( &format!( "__{}", vtypes.len() ));
+    let proto_path: Path = parse_quote!( enumx::proto::#protox );
+
+    (0..vtypes.len()).map( |index| {
+        let variant = &variants[ index ];
+        let vtype = &vtypes[ index ];
+        let pat = variant.pat( name );
+        let value = variant.value();
+        let itype: Type = parse_quote!( [(); #index] );
+
+        let rest = variants.iter().enumerate().filter( |&(i,_)| i != index );
+        let rest_pats = rest.clone().map( |(_,variant)| variant.pat( name )).collect::<Vec<_>>();
+
+        let ( output, mapped, rest_mapped ): ( Type, _, Vec<_> ) = match replaceable_type_param( generics, &vtypes, index ) {
+            Some( type_param ) => {
+                let args = generics.params.iter().map( |param| match param {
+                    GenericParam::Type( param ) if param.ident == *type_param => quote!( _Mapped ),
+                    GenericParam::Type( param ) => { let ident = &param.ident; quote!( #ident )},
+                    GenericParam::Lifetime( param ) => { let lifetime = &param.lifetime; quote!( #lifetime )},
+                    GenericParam::Const( param ) => { let ident = &param.ident; quote!( #ident )},
+                });
+                ( parse_quote!( #name<#(#args),*> ), variant.construct( name, quote!( f( #value ))), rest_pats.clone() )
+            },
+            None => {
+                let types = vtypes.iter().enumerate().map( |(i,vtype)| if i == index { quote!( _Mapped )} else { quote!( #vtype )});
+                let proto_variant = |i: usize, value| {
+                    let proto_name = make_ident( &format!( "_{}", i ));
+                    quote!( #proto_path::#proto_name( #value ))
+                };
+                let rest_mapped = rest.clone().map( |(i,variant)| proto_variant( i, variant.value() )).collect();
+                ( parse_quote!( #proto_path<#(#types),*> ), proto_variant( index, quote!( f( #value ))), rest_mapped )
+            },
+        };
+
+        parse_quote! {
+            impl #impl_generics enumx::MapVariant<#vtype,_Mapped,#itype> for #name #ty_generics #where_clause {
+                type Output = #output;
+
+                fn map_variant_with<_F>( self, f: _F ) -> #output
+                    where _F: ::core::ops::FnOnce( #vtype ) -> _Mapped
+                {
+                    match self {
+                        #pat => #mapped,
+                        #( #rest_pats => #rest_mapped, )*
+                    }
+                }
+            }
+        }
+    }).collect()
 }
 
 /// derives `enumx::Exchange` trait for custom defined `enum`s
 ///
+/// Unit variants are exchanged as `()`, and struct variants or tuple variants of
+/// multiple fields are exchanged as tuples of their field types.
+///
+/// A variant marked with `#[exchange( tag = Tag )]` is exchanged as `enumx::Tagged<Tag, _>`, to be distinguished
+/// from other variants of the same type. A variant marked with `#[exchange( skip )]` is not exchanged, and the enum
+/// has no prototype then, so it can not be exchanged into other enums. Its variants are still accessible by type.
+///
 /// # Examples
 ///
 ///```no_run
@@ -551,11 +1476,46 @@ pub fn derive_proto( input: TokenStream ) -> TokenStream {
 ///     Third(C),
 /// }
 ///```
-#[proc_macro_derive( Exchange )]
+#[proc_macro_derive( Exchange, attributes( exchange ))]
 pub fn derive_exchange( input: TokenStream ) -> TokenStream {
-    let mut output = derive_from_variant( input.clone() );
-    output.extend( derive_proto( input ));
-    output
+    let input = parse_macro_input!( input as DeriveInput );
+
+    exchange_variants( &input, "Only `enum`s can derive `Exchange`." )
+        .and_then( |variants| {
+            let mut expanded = from_variant_impls( &input, &variants );
+            if !variants.iter().any( |variant| variant.skip ) {
+                expanded.extend( proto_impls( &input, &variants )? );
+            }
+            expanded.extend( by_type_impls( &input, &variants ));
+            Ok( expanded )
+        })
+        .unwrap_or_else( |error| error.to_compile_error() )
+        .into()
+}
+
+fn by_type_impls( input: &DeriveInput, variants: &[ExchangeVariant] ) -> proc_macro2::TokenStream {
+    if variants.is_empty() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let name = &input.ident;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let has_variants_impl = quote!( impl #impl_generics enumx::HasVariants for #name #ty_generics #where_clause {} );
+
+    let name: Path = parse_quote!( #name );
+    let get_variant_impls = get_variant_impls( &name, &input.generics, variants );
+
+    // Narrowing, mapping and visiting need every variant to be exchanged.
+    if variants.iter().any( |variant| variant.skip ) {
+        return quote!( #has_variants_impl #(#get_variant_impls)* );
+    }
+
+    let narrow_impls = narrow_impls( &name, &input.generics, variants );
+    let map_variant_impls = map_variant_impls( &name, &input.generics, variants );
+    let accept_impl = accept_impl( &name, &input.generics, variants );
+
+    quote!( #has_variants_impl #(#narrow_impls)* #(#get_variant_impls)* #(#map_variant_impls)* #accept_impl )
 }
 
 fn make_ident( sym: &str ) -> Ident {
@@ -597,16 +1557,17 @@ struct Enum {
 }
 
 struct EnumxTag {
-    enum_ : Option<Enum>,
+    enum_  : Option<Enum>,
+    errors : Errors,
 }
 
 impl EnumxTag {
     fn new() -> Self {
-        EnumxTag{ enum_: None }
+        EnumxTag{ enum_: None, errors: Errors::default() }
     }
 
-    fn parse_type_path_list( input: TokenStream ) -> syn::Result<TypePathList> {
-        Ok( syn::parse::<TypePathList>( input )? )
+    fn parse_type_path_list( input: proc_macro2::TokenStream ) -> syn::Result<TypePathList> {
+        syn::parse2::<TypePathList>( input )
     }
 }
 
@@ -617,39 +1578,93 @@ enum TyPatAttr {
     Gen( Variants ),
 }
 
-fn parse_ty_pat_attr( attr: &Attribute ) -> Option<TyPatAttr> {
+fn parse_ty_pat_attr( attr: &Attribute ) -> syn::Result<Option<TyPatAttr>> {
     if attr.path.leading_colon.is_none() && attr.path.segments.len() == 1 {
         if attr.path.segments.first().unwrap().ident == "ty_pat" {
-            let ts = TokenStream::from( attr.tokens.clone() );
-            let mut iter = ts.into_iter();
-            if let Some( TokenTree::Group( group )) = iter.next() {
+            let mut iter = attr.tokens.clone().into_iter();
+            if let Some( proc_macro2::TokenTree::Group( group )) = iter.next() {
                 let mut iter = group.stream().into_iter();
                 match iter.next() {
-                    Some( TokenTree::Ident( ident )) => match ident.to_string().as_str() {
+                    Some( proc_macro2::TokenTree::Ident( ident )) => match ident.to_string().as_str() {
                         "gen_variants" => {
-                            return Some( TyPatAttr::GenVariants );
+                            return Ok( Some( TyPatAttr::GenVariants ));
                         },
                         "gen" => {
                             let mut variants = IndexSet::new();
-                            let types = EnumxTag::parse_type_path_list( TokenStream::from_iter( iter )).expect("type list");
-                            if types.0.len() == 0 {
-                                return Some( TyPatAttr::GenVariants );
+                            let types = EnumxTag::parse_type_path_list( proc_macro2::TokenStream::from_iter( iter ))?;
+                            if types.0.is_empty() {
+                                return Ok( Some( TyPatAttr::GenVariants ));
                             } else {
                                 types.0.into_iter().for_each( |ty| { variants.insert( TypeIndex( ty, Cell::new(0) )); });
-                                return Some( TyPatAttr::Gen( variants ));
+                                return Ok( Some( TyPatAttr::Gen( variants )));
                             }
                         },
-                        _ => panic!("invalid #[ty_pat] argument: only #[ty_pat(gen_variants)] and #[ty_pat(gen)] are supported"),
+                        _ => return Err( syn::Error::new( ident.span(),
+                            "invalid #[ty_pat] argument: only #[ty_pat(gen_variants)] and #[ty_pat(gen)] are supported" )),
                     },
-                    Some( _ ) => panic!("invalid #[ty_pat] argument"),
-                    None => return Some( TyPatAttr::None ),
+                    Some( tt ) => return Err( syn::Error::new( tt.span(), "invalid #[ty_pat] argument" )),
+                    None => return Ok( Some( TyPatAttr::None )),
                 }
             } else {
-                return Some( TyPatAttr::None );
+                return Ok( Some( TyPatAttr::None ));
             }
         }
     }
-    return None;
+    Ok( None )
+}
+
+// Rewrites the pattern of a `#[ty_pat] match` arm into the one of the ad-hoc enum,
+// the variant of which is given by `variant` from the type.
+fn adhoc_arm_pat( pat: &Pat, errors: &mut Errors, variant: &mut dyn FnMut( &Path ) -> Path ) -> Pat {
+    match pat {
+        Pat::Wild(_) => pat.clone(),
+        Pat::Or( pat_or ) => {
+            let mut pat_or = pat_or.clone();
+            pat_or.cases.iter_mut().for_each( |case| *case = adhoc_arm_pat( case, errors, variant ));
+            Pat::Or( pat_or )
+        },
+        _ => match type_pat( pat ) {
+            Ok(( ty, value_pat )) => {
+                let path = variant( &ty );
+                parse_quote!( #path( #value_pat ))
+            },
+            Err( error ) => {
+                errors.push( error );
+                pat.clone()
+            },
+        },
+    }
+}
+
+// Splits a type pattern into the type and the pattern of its value:
+// `Type(value_pat)` for a value of `Type`, `Type(a, b)` or `Type{ .. }` destructuring `Type` itself,
+// `Type` for any value of `Type`, and `binding @ type_pattern` binding the value.
+fn type_pat( pat: &Pat ) -> syn::Result<( Path, Pat )> {
+    match pat {
+        Pat::TupleStruct( pat_tuple_struct ) if pat_tuple_struct.pat.elems.len() == 1 =>
+            Ok(( pat_tuple_struct.path.clone(), pat_tuple_struct.pat.elems[0].clone() )),
+        Pat::TupleStruct( pat_tuple_struct ) => Ok(( pat_tuple_struct.path.clone(), pat.clone() )),
+        Pat::Struct( pat_struct ) => Ok(( pat_struct.path.clone(), pat.clone() )),
+        Pat::Path( pat_path ) if pat_path.qself.is_none() => Ok(( pat_path.path.clone(), parse_quote!( _ ))),
+        Pat::Ident( pat_ident ) => match &pat_ident.subpat {
+            Some(( at, subpat )) => {
+                let ( ty, value_pat ) = type_pat( subpat )?;
+                let mut binding = pat_ident.clone();
+                binding.subpat = match value_pat {
+                    Pat::Wild(_) => None,
+                    value_pat => Some(( *at, Box::new( value_pat ))),
+                };
+                Ok(( ty, Pat::Ident( binding )))
+            },
+            None if pat_ident.by_ref.is_none() && pat_ident.mutability.is_none() => {
+                let ident = &pat_ident.ident;
+                Ok(( parse_quote!( #ident ), parse_quote!( _ )))
+            },
+            None => Err( syn::Error::new_spanned( pat_ident,
+                "#[enumx] expects a type pattern after `@` in binding by ref/mut, e.g. `ref e @ Type(_)`" )),
+        },
+        _ => Err( syn::Error::new_spanned( pat, "#[enumx] unsupported pattern in match arm" )),
+    }
 }
 
 impl VisitMut for EnumxTag {
@@ -661,9 +1676,11 @@ impl VisitMut for EnumxTag {
             if mac.path.leading_colon.is_none() && mac.path.segments.len() == 1 {
                 let seg = mac.path.segments.first().unwrap();
                 if seg.arguments == PathArguments::None && seg.ident == "Enum" {
-                    let ts = TokenStream::from( mac.tokens.clone() );
                     let mut variants = IndexSet::new();
-                    let types = EnumxTag::parse_type_path_list( ts ).expect("type list");
+                    let types = match EnumxTag::parse_type_path_list( mac.tokens.clone() ) {
+                        Ok( types ) => types,
+                        Err( error ) => return self.errors.push( error ),
+                    };
                     types.0.into_iter().for_each( |ty| {
                         let mut type_ = Type::Path( TypePath{ qself: None, path: ty });
                         self.visit_type_mut( &mut type_ );
@@ -692,75 +1709,61 @@ impl VisitMut for EnumxTag {
                     self.visit_expr_mut( &mut *arm.body );
                 });
 
-                let mut ty_pat_attrs = None;
-                for (index, attr) in expr_match.attrs.iter().enumerate() {
-                    if let Some( attrs ) = parse_ty_pat_attr( attr ) {
-                        ty_pat_attrs = Some(( attrs, index ));
-                        break;
-                    }
-                }
-                ty_pat_attrs.as_ref().map( |(_,index)| expr_match.attrs.remove( *index ));
+                let ty_pat_attr = expr_match.attrs.iter().enumerate()
+                    .find_map( |(index, attr)| parse_ty_pat_attr( attr ).transpose().map( |ty_pat_attr| (index, ty_pat_attr) ));
+                let ty_pat_attrs = match ty_pat_attr {
+                    Some(( index, ty_pat_attr )) => {
+                        let attr = expr_match.attrs.remove( index );
+                        match ty_pat_attr {
+                            Ok( ty_pat_attr ) => Some(( ty_pat_attr, attr )),
+                            Err( error ) => return self.errors.push( error ),
+                        }
+                    },
+                    None => None,
+                };
 
                 if let Some( ty_pat_attrs ) = ty_pat_attrs {
-                    let match_expr = &*expr_match.expr;
-                    let match_span = match_expr.span();
-                    expr_match.expr = Box::new( parse_quote_spanned!( match_span => enumx::ExchangeFrom::exchange_from( #match_expr ) ));
+                    let mut errors = Errors::default();
                     let mut index = 0_u32;
-                    let checked = expr_match.arms.iter_mut().fold( IndexMap::<Path,Cell<u32>>::new(), |mut acc, arm| {
-                        let mut add_type_pattern = |path: &mut Path| {
-                            let mut nth = index;
-                            acc.entry( path.clone() )
-                                .and_modify( |n| { nth = n.get() })
-                                .or_insert( Cell::new( nth ));
-                            if nth == index { index += 1; }
-
-                            let _n = make_ident( &format!( "_{}", nth ));
-                            *path = parse_quote!{ __EnumxAdhocEnum::#_n };
-                        };
-
-                        match &mut arm.pat {
-                            Pat::TupleStruct( pat_tuple_struct ) => {
-                                if pat_tuple_struct.pat.elems.len() > 1 {
-                                    panic!("#[enumx] supports tuple struct variant in newtype form only.");
-                                }
-                                add_type_pattern( &mut pat_tuple_struct.path );
-                                acc
-                            },
-                            Pat::Path( pat_path ) => {
-                                add_type_pattern( &mut pat_path.path );
-                                acc
-                            },
-                            Pat::Wild(_) if arm.guard.is_none() => {
-                                acc
-                            },
-                            _ => {
-                                let pat_ident;
-                                if let Pat::Ident( ident ) = &mut arm.pat {
-                                    if ident.by_ref.is_some() || ident.mutability.is_some() || ident.subpat.is_some() {
-                                        panic!("#[enumx] dost not support ident in arm with ref/mut/sub pattern.");
-                                    } else {
-                                        pat_ident = Some( ident.clone() );
-                                    }
-                                } else {
-                                    panic!("#[enumx] unsupported pattern in match arm");
-                                }
-
-                                pat_ident.map( |pat_ident| {
-                                    let ident = &pat_ident.ident;
-                                    let mut path: Path = parse_quote!( #ident );
-                                    add_type_pattern( &mut path );
-                                    arm.pat = parse_quote!( #path(_) );
-                                });
+                    let mut checked = IndexMap::<Path,Cell<u32>>::new();
+                    let pats = expr_match.arms.iter().map( |arm| adhoc_arm_pat( &arm.pat, &mut errors, &mut |path| {
+                        let mut nth = index;
+                        checked.entry( path.clone() )
+                            .and_modify( |n| { nth = n.get() })
+                            .or_insert( Cell::new( nth ));
+                        if nth == index { index += 1; }
+
+                        let _n = make_ident( &format!( "_{}", nth ));
+                        parse_quote!{ __EnumxAdhocEnum::#_n }
+                    })).collect::<Vec<_>>();
+
+                    // Matching an expression of `&enum` or `&mut enum` type binds the variants by reference,
+                    // which is only known by the syntax here if it is written as `&enum` or `&mut enum`.
+                    let borrowed = matches!( &*expr_match.expr, Expr::Reference(_) );
+                    if borrowed && ty_pat_attrs.0 != TyPatAttr::None {
+                        errors.error( &ty_pat_attrs.1, "#[ty_pat(gen_variants)] and #[ty_pat(gen ..)] are not supported in matching borrowed enums" );
+                    } else if ty_pat_attrs.0 == TyPatAttr::GenVariants && self.enum_.is_none() {
+                        errors.error( &ty_pat_attrs.1, "#[ty_pat(gen_variants)] expects the variants to be given by `Enum!()` in the signature" );
+                    }
+                    if !errors.is_empty() {
+                        return self.errors.extend( errors );
+                    }
 
-                                acc
-                            },
-                        }
-                    });
+                    expr_match.arms.iter_mut().zip( pats ).for_each( |( arm, pat )| arm.pat = pat );
+                    let match_expr = &*expr_match.expr;
+                    let match_span = match_expr.span();
+                    expr_match.expr = parse_quote_spanned!( match_span =>
+                        enumx::ty_pat::with( #match_expr, |src, mode| {
+                            #[allow( unused_imports )]
+                            use enumx::ty_pat::{ByMutKind as _, ByRefKind as _, ByValueKind as _};
+                            mode.ty_pat_mode().exchange( src )
+                        })
+                    );
 
                     let checked = IndexSet::<TypeIndex>::from_iter( checked.clone().into_iter().map( |(t,i)| TypeIndex(t,i) ));
                     let unexhausted = match &ty_pat_attrs.0 {
                         TyPatAttr::None => Vec::new(),
-                        TyPatAttr::GenVariants => self.enum_.as_ref().expect("def_impls!( variants ... ) parsed").variants.difference( &checked ).collect::<Vec<_>>(),
+                        TyPatAttr::GenVariants => self.enum_.as_ref().unwrap().variants.difference( &checked ).collect::<Vec<_>>(),
                         TyPatAttr::Gen( variants ) => variants.difference( &checked ).collect::<Vec<_>>(),
                     };
 
@@ -779,12 +1782,29 @@ impl VisitMut for EnumxTag {
                     let (checked_types, checked_indices): (Vec<_>, Vec<_>) = checked.iter().map( |TypeIndex(t,i)| (t,i) ).unzip();
                     let checked_indices = checked_indices.iter().map( |n| make_ident( &format!( "_{}", n.get() )));
 
+                    let mut mode_predicates = Vec::<WherePredicate>::new();
+                    let mut adhoc_ty = |path: &Path| {
+                        let mut path = path.clone();
+                        AdhocLifetime.visit_path_mut( &mut path );
+                        adhoc_variant_ty( &path, &mut mode_predicates )
+                    };
+                    let checked_types = checked_types.into_iter().map( &mut adhoc_ty ).collect::<Vec<_>>();
+                    let unexhausted_types = unexhausted_types.into_iter().map( &mut adhoc_ty ).collect::<Vec<_>>();
+
                     let adhoc_enum = quote_spanned!{ match_span =>
-                        #[derive( ::enumx::FromVariant, ::enumx::Proto )]
-                        enum __EnumxAdhocEnum {
+                        #[derive( ::enumx::FromVariant )]
+                        enum __EnumxAdhocEnum<'__enumx, __EnumxMode>
+                            where #( #mode_predicates, )*
+                        {
                             #( #checked_indices( #checked_types ), )*
                             #( #unexhausted_indices( #unexhausted_types ), )*
                         }
+
+                        impl<'__enumx, __EnumxMode> enumx::ty_pat::Adhoc for __EnumxAdhocEnum<'__enumx, __EnumxMode>
+                            where #( #mode_predicates, )*
+                        {
+                            type Mode = __EnumxMode;
+                        }
                     };
 
                     *expr = syn::parse::<Expr>( quote_spanned!{ match_span => {
@@ -800,7 +1820,45 @@ impl VisitMut for EnumxTag {
     }
 }
 
+/// The variant type of the ad-hoc enum, which is borrowed if the matched enum is. `TyPat::<T>` of a reference or
+/// tuple type `T` is kept as it is in every mode, e.g. `TyPat::<(&String,&i32)>` for a struct variant of a borrowed
+/// enum. Otherwise the type is mapped by the mode, the bound of which is collected into `predicates`.
+fn adhoc_variant_ty( path: &Path, predicates: &mut Vec<WherePredicate> ) -> Type {
+    if let Some( segment ) = path.segments.last() {
+        if segment.ident == "TyPat" {
+            if let PathArguments::AngleBracketed( args ) = &segment.arguments {
+                if let Some( GenericArgument::Type( ty @ Type::Reference(_) )) | Some( GenericArgument::Type( ty @ Type::Tuple(_) )) = args.args.first() {
+                    return parse_quote!( <__EnumxMode as enumx::ty_pat::Keep<'__enumx, #ty>>::Variant );
+                }
+            }
+        }
+    }
+    predicates.push( parse_quote!( __EnumxMode: enumx::ty_pat::Mode<'__enumx, #path> ));
+    parse_quote!( <__EnumxMode as enumx::ty_pat::Mode<'__enumx, #path>>::Variant )
+}
+
+/// Replaces non-static lifetimes, either named or elided, with the one of the ad-hoc enum.
+struct AdhocLifetime;
+
+impl VisitMut for AdhocLifetime {
+    fn visit_type_reference_mut( &mut self, type_reference: &mut syn::TypeReference ) {
+        if type_reference.lifetime.is_none() {
+            type_reference.lifetime = Some( parse_quote!( '__enumx ));
+        }
+        visit_mut::visit_type_reference_mut( self, type_reference );
+    }
+
+    fn visit_lifetime_mut( &mut self, lifetime: &mut syn::Lifetime ) {
+        if lifetime.ident != "static" {
+            *lifetime = parse_quote!( '__enumx );
+        }
+    }
+}
+
 /// tag an `fn` with `#[enumx]` to enable "type pattern matching" in `match` expressions that are tagged with `#[ty_pat]`/`#[ty_pat(gen_variants)]/`#[ty_pat(gen A,B,..)]`.
+///
+/// A `#[ty_pat] match` on an expression of type `&Enum` or `&mut Enum`, e.g. `&value` or a `&Enum!(..)` parameter,
+/// binds the variants by reference. A `&mut Enum` held in a variable is moved into the match unless reborrowed.
 #[proc_macro_attribute]
 pub fn enumx( _args: TokenStream, input: TokenStream ) -> TokenStream {
     if let Ok( mut stmt ) = syn::parse::<Stmt>( input.clone() ) {
@@ -810,28 +1868,36 @@ pub fn enumx( _args: TokenStream, input: TokenStream ) -> TokenStream {
                 enumx_tag.visit_signature_mut( &mut item_fn.sig );
                 enumx_tag.visit_block_mut( &mut *item_fn.block );
 
-                let expanded = quote_spanned!( item_fn.span() => #item_fn );
+                let errors = enumx_tag.errors.to_compile_error();
+                let expanded = quote_spanned!( item_fn.span() => #item_fn #errors );
                 return expanded.into();
             },
             Stmt::Local(_) => {
                 let mut enumx_tag = EnumxTag::new();
                 visit_mut::visit_stmt_mut( &mut enumx_tag, &mut stmt );
 
-                let expanded = quote_spanned!( stmt.span() => #stmt );
+                let errors = enumx_tag.errors.to_compile_error();
+                let expanded = quote_spanned!( stmt.span() => #errors #stmt );
                 return expanded.into();
             },
             _ => (),
         }
-    } else if let Ok( mut expr_closure ) = syn::parse::<ExprClosure>( input ) {
+    } else if let Ok( mut expr_closure ) = syn::parse::<ExprClosure>( input.clone() ) {
         let mut enumx_tag = EnumxTag::new();
         enumx_tag.visit_return_type_mut( &mut expr_closure.output );
         enumx_tag.visit_expr_mut( &mut *expr_closure.body );
 
-        let expanded = quote_spanned!( expr_closure.span() => #expr_closure );
+        let expanded = if enumx_tag.errors.is_empty() {
+            quote_spanned!( expr_closure.span() => #expr_closure )
+        } else {
+            let errors = enumx_tag.errors.to_compile_error();
+            quote_spanned!( expr_closure.span() => { #errors #expr_closure })
+        };
         return expanded.into();
     }
 
-    panic!( "#[enumx] for functions, closures, local let-bindings and impl blocks only!!!" );
+    let input = proc_macro2::TokenStream::from( input );
+    syn::Error::new_spanned( input, "#[enumx] for functions, closures and local let-bindings only" ).to_compile_error().into()
 }
 
 #[derive( Default )]
@@ -840,6 +1906,7 @@ struct IterativeImpl {
     index   : usize,
     vnames  : Vec<Ident>,
     vtypes  : Vec<Type>,
+    errors  : Errors,
 }
 
 impl VisitMut for IterativeImpl {
@@ -849,8 +1916,11 @@ impl VisitMut for IterativeImpl {
         if let Expr::Macro( expr_macro ) = expr {
             if let Some( name ) = path_ident_name( &expr_macro.mac.path ) {
                 if name == "_match" {
-                    let MatchInput{ self_expr, arm_expr } = expr_macro.mac.parse_body::<MatchInput>()
-                        .expect("def_impls!{ impl }: expects _match!( expr => expr ) or _match!( expr )");
+                    let MatchInput{ self_expr, arm_expr } = match expr_macro.mac.parse_body::<MatchInput>() {
+                        Ok( match_input ) => match_input,
+                        Err( _ ) => return self.errors.error( &expr_macro.mac,
+                            "def_impls!{ impl }: expects _match!( expr => expr ) or _match!( expr )" ),
+                    };
                     let mut match_expander = MatchExpander::from( self );
                     let arm_exprs = (0..=self.index).fold( Vec::new(), |mut arm_exprs, i| {
                         match_expander.index = i;
@@ -859,6 +1929,7 @@ impl VisitMut for IterativeImpl {
                         arm_exprs.push( arm_expr );
                         arm_exprs
                     });
+                    self.errors.extend( match_expander.errors );
 
                     let self_ty = &self.self_ty;
                     let enum_ty = (0..=self.index).map( |_| self_ty.clone() ).collect::<Vec<_>>();
@@ -903,6 +1974,7 @@ struct MatchExpander {
     self_ty : Option<Type>,
     index   : usize,
     vtypes  : Vec<Type>,
+    errors  : Errors,
 }
 
 impl MatchExpander {
@@ -911,6 +1983,7 @@ impl MatchExpander {
             self_ty : iterative_impl.self_ty.clone(),
             index   : 0,
             vtypes  : iterative_impl.vtypes.clone(),
+            errors  : Errors::default(),
         }
     }
 }
@@ -926,8 +1999,11 @@ impl VisitMut for MatchExpander {
                         if expr_macro.mac.tokens.is_empty() || expr_macro.mac.tokens.to_string() == "self" {
                             *expr = parse_quote_spanned!( expr.span() => __variant );
                         } else {
-                            let other = expr_macro.mac.parse_body::<Expr>()
-                                .expect("def_impls!{ impl }: _variant!()'s argument should be an expression");
+                            let other = match expr_macro.mac.parse_body::<Expr>() {
+                                Ok( other ) => other,
+                                Err( _ ) => return self.errors.error( &expr_macro.mac.tokens,
+                                    "def_impls!{ impl }: _variant!()'s argument should be an expression" ),
+                            };
                             let self_ty = self.self_ty.as_ref().unwrap();
                             let vname = make_ident( &format!( "_{}", self.index ));
                             *expr = parse_quote_spanned! { expr.span() =>
@@ -940,8 +2016,11 @@ impl VisitMut for MatchExpander {
                         }
                     },
                     "_enum" => {
-                        let mut inner = expr_macro.mac.parse_body::<Expr>()
-                            .expect("def_impls!{ impl }: _enum!()'s argument should be an expression");
+                        let mut inner = match expr_macro.mac.parse_body::<Expr>() {
+                            Ok( inner ) => inner,
+                            Err( _ ) => return self.errors.error( &expr_macro.mac,
+                                "def_impls!{ impl }: _enum!()'s argument should be an expression" ),
+                        };
                         self.visit_expr_mut( &mut inner );
                         let self_ty = self.self_ty.as_ref().unwrap();
                         let vname = make_ident( &format!( "_{}", self.index ));
@@ -965,7 +2044,7 @@ impl VisitMut for MatchExpander {
                         let vtype = &self.vtypes[ self.index ];
                         *node = parse_quote!( #vtype );
                     } else {
-                        panic!( "{}", "def_impls!{ impl }: Variant!() should have no argument" );
+                        self.errors.error( &type_macro.mac.tokens, "def_impls!{ impl }: _Variant!() should have no argument" );
                     }
                 }
             }
@@ -977,6 +2056,7 @@ struct EnumWhere {
     nth_variant       : usize,
     variant_types     : Vec<Type>,
     contains_variants : bool,
+    errors            : Errors,
 }
 
 impl EnumWhere {
@@ -985,10 +2065,12 @@ impl EnumWhere {
             nth_variant       : 0,
             variant_types     ,
             contains_variants : false,
+            errors            : Errors::default(),
         }
     }
 
-    fn expand( where_clause: &mut Option<WhereClause>, variant_types: Vec<Type> ) {
+    fn expand( where_clause: &mut Option<WhereClause>, variant_types: Vec<Type> ) -> syn::Result<()> {
+        let mut errors = Errors::default();
         if let Some( where_clause ) = where_clause.as_mut() {
             let mut predicates = Punctuated::new();
             mem::swap( &mut predicates, &mut where_clause.predicates );
@@ -996,7 +2078,9 @@ impl EnumWhere {
                 let variant_count = variant_types.len();
                 let mut enum_where = EnumWhere::new( variant_types.clone() );
                 enum_where.visit_where_predicate( &predicate );
-                if enum_where.contains_variants {
+                if !enum_where.errors.is_empty() {
+                    errors.extend( enum_where.errors );
+                } else if enum_where.contains_variants {
                     for i in 0..variant_count {
                         let mut predicate = predicate.clone();
                         enum_where.nth_variant = i;
@@ -1008,6 +2092,7 @@ impl EnumWhere {
                 }
             }
         }
+        errors.into_result()
     }
 }
 
@@ -1020,7 +2105,7 @@ impl<'a> Visit<'a> for EnumWhere {
                 if type_macro.mac.tokens.is_empty() {
                     self.contains_variants = true;
                 } else {
-                    panic!( "{}", "def_impls!{ impl }: Variants!() should have no argument" );
+                    self.errors.error( &type_macro.mac.tokens, "def_impls!{ impl }: _Variants!() should have no argument" );
                 }
             }
         }
@@ -1033,44 +2118,40 @@ impl VisitMut for EnumWhere {
 
         if let Type::Macro( type_macro ) = node {
             if let Some( name ) = path_ident_name( &type_macro.mac.path ) {
-                if name == "_Variants" {
-                    if type_macro.mac.tokens.is_empty() {
-                        let ty = &self.variant_types[ self.nth_variant ];
-                        *node = parse_quote!( #ty );
-                    } else {
-                        panic!( "{}", "def_impls!{ impl }: Variants!() should have no argument" );
-                    }
+                // `_Variants!()` with arguments has been reported by `visit_type_macro()`.
+                if name == "_Variants" && type_macro.mac.tokens.is_empty() {
+                    let ty = &self.variant_types[ self.nth_variant ];
+                    *node = parse_quote!( #ty );
                 }
             }
         }
     }
 }
 
-fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) -> TokenStream {
+fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) -> syn::Result<proc_macro2::TokenStream> {
     let mut iterative_impl = IterativeImpl::default();
     let mut def_enum_in_self_ty = None;
     let mut item_enum = item_enum;
 
     loop {
         if let Type::Macro( type_macro ) = &*item_impl.self_ty {
-            let ident = path_ident( &type_macro.mac.path ).expect("def_impls!{}: the path of the macro as self type in impl blocks should be an ident");
+            let ident = path_ident( &type_macro.mac.path ).ok_or_else( || syn::Error::new_spanned( &type_macro.mac.path,
+                "def_impls!{}: the path of the macro as self type in impl blocks should be an ident" ))?;
 
             if ident == "_def" {
-                def_enum_in_self_ty = Some( type_macro.mac.parse_body::<ItemEnum>().expect( "def_impls!{}: expect _def!{ ItemEnum }" ));
+                def_enum_in_self_ty = Some( type_macro.mac.parse_body::<ItemEnum>()? );
                 item_enum = def_enum_in_self_ty.as_ref();
                 break;
             }
 
-            let mut range;
-            match type_macro.mac.delimiter {
+            let mut range = match type_macro.mac.delimiter {
                 MacroDelimiter::Bracket(_) => match type_macro.mac.parse_body::<ExprRange>() {
-                    Ok( expr_range ) => {
-                        range = parse_range( expr_range ).expect( &format!( "expect {ident}![ literial_range ], e.g. {ident}![ 1..=16 ]", ident = ident ));
-                    },
-                    Err( _ ) => panic!( "expect {}![ expr_range ]", ident ),
+                    Ok( expr_range ) => parse_range( expr_range ).ok_or_else( || syn::Error::new_spanned( &type_macro.mac.tokens,
+                        format!( "expect {ident}![ literial_range ], e.g. {ident}![ 1..=16 ]", ident = ident )))?,
+                    Err( _ ) => return Err( syn::Error::new_spanned( &type_macro.mac.tokens, format!( "expect {}![ expr_range ]", ident ))),
                 },
-                _ => panic!( "expect {}![]", ident ),
-            }
+                _ => return Err( syn::Error::new_spanned( &type_macro.mac, format!( "expect {}![]", ident ))),
+            };
 
             if range.start > 0 {
                 range.start -= 1;
@@ -1117,7 +2198,7 @@ fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) ->
                     iterative_impl.self_ty = Some( parse_quote!( #self_ty ));
                 }
 
-                EnumWhere::expand( &mut item_impl.generics.where_clause, vtypes.clone() );
+                EnumWhere::expand( &mut item_impl.generics.where_clause, vtypes.clone() )?;
 
                 item_impl.items = item_impl.items.into_iter().map( |mut item| {
                     match item {
@@ -1125,15 +2206,24 @@ fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) ->
                             iterative_impl.index = index;
                             iterative_impl.visit_block_mut( &mut impl_item_method.block );
                         },
+                        ImplItem::Const( ref mut impl_item_const ) => {
+                            let mut match_expander = MatchExpander::from( &iterative_impl );
+                            match_expander.visit_expr_mut( &mut impl_item_const.expr );
+                            iterative_impl.errors.extend( match_expander.errors );
+                        },
                         _ => (),
                     }
                     item
                 }).collect();
+
+                // The same errors would be found in the impls of other enums in range.
+                mem::take( &mut iterative_impl.errors ).into_result()?;
+
                 impls.push( item_impl );
             }
 
             let expanded = quote_spanned!( item_impl.span() => #( #impls )* );
-            return expanded.into();
+            return Ok( expanded );
         }
         break;
     }
@@ -1147,6 +2237,7 @@ fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) ->
             item_impl.self_ty = parse_quote!( #enum_ident #ty_generics );
         }
 
+        let mut errors = Errors::default();
         for variant in item_enum.variants.iter() {
             loop {
                 if let Fields::Unnamed( fields_unnamed ) = &variant.fields {
@@ -1159,12 +2250,14 @@ fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) ->
                         }
                     }
                 }
-                panic!( "expect `{}( VariantType )`", variant.ident );
+                errors.error( variant, format!( "expect `{}( VariantType )`", variant.ident ));
+                break;
             }
         }
+        errors.into_result()?;
 
         let variant_count = item_enum.variants.len();
-        EnumWhere::expand( &mut item_impl.generics.where_clause, iterative_impl.vtypes.clone() );
+        EnumWhere::expand( &mut item_impl.generics.where_clause, iterative_impl.vtypes.clone() )?;
 
         item_impl.items = item_impl.items.into_iter().map( |mut item| {
             match item {
@@ -1172,16 +2265,23 @@ fn expand_enum_impl( mut item_impl: ItemImpl, item_enum: Option<&ItemEnum> ) ->
                     iterative_impl.index = variant_count-1;
                     iterative_impl.visit_block_mut( &mut impl_item_method.block );
                 },
+                ImplItem::Const( ref mut impl_item_const ) => {
+                    let mut match_expander = MatchExpander::from( &iterative_impl );
+                    match_expander.visit_expr_mut( &mut impl_item_const.expr );
+                    iterative_impl.errors.extend( match_expander.errors );
+                },
                 _ => (),
             }
             item
         }).collect();
+        iterative_impl.errors.into_result()?;
 
         let expanded = quote!( #item_impl );
-        return expanded.into();
+        Ok( expanded )
     } else {
-        panic!( "{}", "The enum's variants are unknown to `def_impls!{}`. Consider using `_def!{}` to provide the variants of the enum defined elsewhere." );
-    };
+        Err( syn::Error::new_spanned( &item_impl.self_ty,
+            "The enum's variants are unknown to `def_impls!{}`. Consider using `_def!{}` to provide the variants of the enum defined elsewhere." ))
+    }
 }
 
 #[derive( Default )]
@@ -1204,21 +2304,22 @@ impl Parse for VariantLabel {
                 if input.peek( Token![_] ) {
                     input.parse::< Token![_] >()?;
                     if !input.is_empty() {
-                        panic!("#[variant( which_impl_trait => _ )] expected, extra tokens after `_` got ");
+                        return Err( input.error( "#[variant( which_impl_trait => _ )] expected, extra tokens after `_` got" ));
                     }
                     Ok( VariantLabel{ which_impl_trait: ident, label: None })
                 } else {
                     Ok( VariantLabel{ which_impl_trait: ident, label: Some( input.parse::<Ident>()? )})
                 }
             } else {
-                panic!("#[variant]: expected `.`");
+                Err( input.error( "#[variant]: expected `=>`" ))
             }
         }
     }
 }
 
-fn take_variant_label( attrs: &mut Vec<Attribute>, which_impl_trait: &Option<Ident> ) -> Option<VariantLabel> {
+fn take_variant_label( attrs: &mut Vec<Attribute>, which_impl_trait: &Option<Ident> ) -> syn::Result<Option<VariantLabel>> {
     let mut variant_label = None::<VariantLabel>;
+    let mut errors = Errors::default();
 
     let mut attributes = Vec::new();
     mem::swap( &mut attributes, attrs );
@@ -1226,12 +2327,15 @@ fn take_variant_label( attrs: &mut Vec<Attribute>, which_impl_trait: &Option<Ide
     for attr in attributes {
         if attr.path.leading_colon.is_none() && attr.path.segments.len() == 1 {
             if attr.path.segments.first().unwrap().ident == "variant" {
-                let ts = TokenStream::from( attr.tokens.clone() );
-                let mut iter = ts.into_iter();
-                if let Some( TokenTree::Group( group )) = iter.next() {
-                    let inner_stream = group.stream();
-                    let label: VariantLabel = syn::parse::<VariantLabel>( inner_stream )
-                        .expect("expect #[variant( tag )] or #[variant( impl_tag.variant_tag )]");
+                let mut iter = attr.tokens.clone().into_iter();
+                if let Some( proc_macro2::TokenTree::Group( group )) = iter.next() {
+                    let label = match syn::parse2::<VariantLabel>( group.stream() ) {
+                        Ok( label ) => label,
+                        Err( error ) => {
+                            errors.push( error );
+                            continue;
+                        },
+                    };
                     if &label.which_impl_trait == which_impl_trait {
                         variant_label = Some( label );
                     } else {
@@ -1247,13 +2351,14 @@ fn take_variant_label( attrs: &mut Vec<Attribute>, which_impl_trait: &Option<Ide
         attrs.push( attr );
     }
 
-    return variant_label;
+    errors.into_result().map( |_| variant_label )
 }
 
 struct Sum {
     which_impl_trait : Option<Ident>,
     variant_count    : usize,
     labeled_variants : IndexMap<Ident, usize>,
+    errors           : Errors,
 }
 
 impl Sum {
@@ -1262,6 +2367,7 @@ impl Sum {
             which_impl_trait ,
             variant_count    : 0,
             labeled_variants : IndexMap::new(),
+            errors           : Errors::default(),
         }
     }
 }
@@ -1274,7 +2380,9 @@ impl VisitMut for Sum {
                 let mut is_variant = true;
                 loop {
                     let self_variant_count = self.variant_count;
-                    if let Some( variant_label ) = take_variant_label( &mut $expr_.attrs, &self.which_impl_trait ) {
+                    let variant_label = take_variant_label( &mut $expr_.attrs, &self.which_impl_trait )
+                        .unwrap_or_else( |error| { self.errors.push( error ); None });
+                    if let Some( variant_label ) = variant_label {
                         if let Some( label ) = variant_label.label {
                             if label != "" {
                                 let variant_count = self.labeled_variants
@@ -1354,6 +2462,113 @@ impl VisitMut for Sum {
     fn visit_item_mut( &mut self, _item: &mut Item ) {}
 }
 
+// Tags every exit point of a function with `#[variant]`, for `#[sum( auto )]`.
+struct SumAuto;
+
+impl SumAuto {
+    fn tag_block( block: &mut Block ) {
+        if let Some( Stmt::Expr( expr )) = block.stmts.last_mut() {
+            SumAuto::tag_expr( expr );
+        }
+    }
+
+    fn tag_expr( expr: &mut Expr ) {
+        match expr {
+            Expr::Block( expr_block ) => SumAuto::tag_block( &mut expr_block.block ),
+            Expr::Unsafe( expr_unsafe ) => SumAuto::tag_block( &mut expr_unsafe.block ),
+            Expr::Group( expr_group ) => SumAuto::tag_expr( &mut expr_group.expr ),
+            Expr::Paren( expr_paren ) => SumAuto::tag_expr( &mut expr_paren.expr ),
+            Expr::If( expr_if ) => {
+                SumAuto::tag_block( &mut expr_if.then_branch );
+                if let Some(( _, else_branch )) = &mut expr_if.else_branch {
+                    SumAuto::tag_expr( else_branch );
+                }
+            },
+            Expr::Match( expr_match ) => for arm in &mut expr_match.arms {
+                SumAuto::tag_expr( &mut arm.body );
+            },
+            // The values of `break` are the exit points of a loop, which never ends without them.
+            Expr::Loop( expr_loop ) => {
+                let mut breaks = SumAutoBreak{ label: expr_loop.label.as_ref().map( |label| label.name.clone() ), depth: 0 };
+                breaks.visit_block_mut( &mut expr_loop.body );
+            },
+            Expr::Break(_) | Expr::Continue(_) | Expr::Return(_) => (),
+            Expr::Macro( expr_macro ) if expr_macro.mac.path.get_ident().map( |ident|
+                ident == "panic" || ident == "unreachable" || ident == "unimplemented" || ident == "todo"
+            ).unwrap_or( false ) => (),
+            _ => if !is_tagged_variant( expr ) {
+                let the_expr = mem::replace( expr, Expr::Verbatim( proc_macro2::TokenStream::new() ));
+                *expr = Expr::Group( ExprGroup {
+                    attrs       : vec![ parse_quote!( #[variant] )],
+                    group_token : syn::token::Group( the_expr.span() ),
+                    expr        : Box::new( the_expr ),
+                });
+            },
+        }
+    }
+}
+
+impl VisitMut for SumAuto {
+    fn visit_expr_mut( &mut self, expr: &mut Expr ) {
+        match expr {
+            Expr::Async(_) | Expr::Closure(_) => (),
+            Expr::Return( expr_return ) => {
+                if let Some( returned ) = &mut expr_return.expr {
+                    visit_mut::visit_expr_mut( self, returned );
+                    SumAuto::tag_expr( returned );
+                }
+            },
+            _ => visit_mut::visit_expr_mut( self, expr ),
+        }
+    }
+
+    fn visit_item_mut( &mut self, _item: &mut Item ) {}
+}
+
+// Tags the values of `break` expressions which exit the loop of the given label, or the outermost unlabeled loop.
+struct SumAutoBreak {
+    label : Option<Lifetime>,
+    depth : usize,
+}
+
+impl VisitMut for SumAutoBreak {
+    fn visit_expr_mut( &mut self, expr: &mut Expr ) {
+        match expr {
+            Expr::Async(_) | Expr::Closure(_) => (),
+            Expr::Loop(_) | Expr::While(_) | Expr::ForLoop(_) => {
+                self.depth += 1;
+                visit_mut::visit_expr_mut( self, expr );
+                self.depth -= 1;
+            },
+            Expr::Break( expr_break ) => {
+                let exits = match &expr_break.label {
+                    Some( label ) => self.label.as_ref() == Some( label ),
+                    None => self.depth == 0,
+                };
+                if let Some( value ) = &mut expr_break.expr {
+                    self.visit_expr_mut( value );
+                    if exits {
+                        SumAuto::tag_expr( value );
+                    }
+                }
+            },
+            _ => visit_mut::visit_expr_mut( self, expr ),
+        }
+    }
+
+    fn visit_item_mut( &mut self, _item: &mut Item ) {}
+}
+
+// Checks if the expression starts with `#[variant]` or `#[variant(..)]`.
+fn is_tagged_variant( expr: &Expr ) -> bool {
+    let mut iter = quote!( #expr ).into_iter();
+    match ( iter.next(), iter.next() ) {
+        ( Some( proc_macro2::TokenTree::Punct( punct )), Some( proc_macro2::TokenTree::Group( group ))) if punct.as_char() == '#' =>
+            group.stream().into_iter().next().map( |tt| tt.to_string() == "variant" ).unwrap_or( false ),
+        _ => false,
+    }
+}
+
 struct ReplaceIdent {
     placehoder : Ident,
     real_ident : Ident,
@@ -1375,8 +2590,9 @@ impl VisitMut for ReplaceIdent {
     fn visit_item_mut( &mut self, _item: &mut Item ) {}
 }
 
-#[derive( Default )]
+#[derive( Clone, Default )]
 struct SumArgs {
+    auto             : bool,
     which_impl_trait : Option<Ident>,
     impl_generics    : Option<Punctuated<GenericParam, Token![,]>>,
     trait_path       : Option<Path>,
@@ -1385,6 +2601,20 @@ struct SumArgs {
 
 impl Parse for SumArgs {
     fn parse( input: ParseStream ) -> syn::Result<Self> {
+        let fork = input.fork();
+        if fork.parse::<Ident>().map( |ident| ident == "auto" ).unwrap_or( false )
+            && ( fork.is_empty() || fork.peek( Token![,] ))
+        {
+            input.parse::<Ident>()?;
+            if input.peek( Token![,] ) {
+                input.parse::<Token![,]>()?;
+            }
+            if input.peek( Ident ) && input.peek2( Token![=>] ) {
+                return Err( input.error( "#[sum( auto, which_impl_trait => ... )] is not supported" ));
+            }
+            return Ok( SumArgs{ auto: true, ..input.parse::<SumArgs>()? });
+        }
+
         if input.is_empty() {
             Ok( SumArgs::default() )
         } else {
@@ -1412,6 +2642,7 @@ impl Parse for SumArgs {
 
                 if input.is_empty() {
                     Ok( SumArgs {
+                        auto             : false,
                         which_impl_trait ,
                         impl_generics    ,
                         trait_path       ,
@@ -1420,6 +2651,7 @@ impl Parse for SumArgs {
                 } else {
                     input.parse::<Token![for]>()?;
                     Ok( SumArgs {
+                        auto             : false,
                         which_impl_trait ,
                         impl_generics    ,
                         trait_path       ,
@@ -1428,6 +2660,7 @@ impl Parse for SumArgs {
                 }
             } else if input.peek( Ident ) {
                 Ok( SumArgs {
+                        auto             : false,
                         which_impl_trait ,
                         impl_generics    : None,
                         trait_path       : None,
@@ -1436,9 +2669,10 @@ impl Parse for SumArgs {
             } else {
                 input.parse::<Token![_]>()?;
                 if !input.is_empty() {
-                    panic!("#[sum( which_impl_trait => _ )] expected, extra tokens after `_` got ");
+                    return Err( input.error( "#[sum( which_impl_trait => _ )] expected, extra tokens after `_` got" ));
                 }
                 Ok( SumArgs {
+                        auto             : false,
                         which_impl_trait ,
                         impl_generics    : None,
                         trait_path       : None,
@@ -1449,6 +2683,63 @@ impl Parse for SumArgs {
     }
 }
 
+// Translates a bound of the returned `impl Trait` into the generics and the path
+// accepted by `impl_all_traits!{}`. Auto traits and `?Sized` are skipped because
+// the generated enum gets them for free if all its variants do.
+fn sum_trait_path( trait_bound: &TraitBound ) -> Option<(Option<Punctuated<GenericParam, Token![,]>>, Path)> {
+    if let TraitBoundModifier::Maybe(_) = trait_bound.modifier {
+        return None;
+    }
+
+    let mut path = trait_bound.path.clone();
+    let mut impl_generics = None;
+
+    let path_seg = path.segments.last_mut()?;
+    match path_seg.ident.to_string().as_str() {
+        "Send" | "Sync" | "Unpin" | "UnwindSafe" | "RefUnwindSafe" | "Sized" => return None,
+        "Fn" | "FnMut" | "FnOnce" => {
+            path_seg.arguments = PathArguments::AngleBracketed( parse_quote_spanned!( path_seg.arguments.span() => <Args> ));
+            let mut punct = Punctuated::new();
+            punct.push( parse_quote_spanned!( path_seg.arguments.span() => Args ));
+            impl_generics = Some( punct );
+        },
+        _ => {
+            // associated type bindings, e.g. `Item=u8`, are checked by the returned `impl Trait`
+            let mut args = Punctuated::<GenericArgument, Token![,]>::new();
+            if let PathArguments::AngleBracketed( angle_bracketed ) = &path_seg.arguments {
+                for arg in &angle_bracketed.args {
+                    if let GenericArgument::Type( ty ) = arg {
+                        args.push( GenericArgument::Type( ty.clone() ));
+                    }
+                }
+            }
+            path_seg.arguments = if args.is_empty() {
+                PathArguments::None
+            } else {
+                PathArguments::AngleBracketed( parse_quote_spanned!( path_seg.arguments.span() => <#args> ))
+            };
+        },
+    }
+
+    Some(( impl_generics, std_trait_path( path )))
+}
+
+// Completes the path of a trait in a std module which `impl_trait!{}` recognizes by
+// full path, e.g. `fmt::Display` as `std::fmt::Display`. A bare name, e.g. `Display`,
+// is kept as is, and resolved by `impl_trait!{}` to the companion macro in scope,
+// which is the std one exported by `enumx::export` unless a user-defined trait of
+// the same name is imported.
+fn std_trait_path( path: Path ) -> Path {
+    if path.leading_colon.is_some() || path.segments.len() == 1 {
+        return path;
+    }
+
+    match path.segments[0].ident.to_string().as_str() {
+        "borrow" | "error" | "fmt" | "future" | "hash" | "io" | "iter" | "ops" => parse_quote_spanned!( path.span() => std::#path ),
+        _ => path,
+    }
+}
+
 /// collects all returned values in exit branches of the function, into an enum type, returning an `impl` trait
 ///
 /// #Examples
@@ -1463,89 +2754,317 @@ impl Parse for SumArgs {
 ///     }
 /// }
 /// ```
+///
+/// `async` blocks tagged with `#[variant]` are collected into one `impl Future`.
+/// An `async fn` not returning `impl Trait` collects them too.
+///
+/// `#[sum( auto )]` wraps every exit point of the function, i.e. the tail expression,
+/// `return` expressions and the final expressions of `if`/`match` branches, without
+/// `#[variant]` attributes.
+///
+/// `#[sum]` also works on closures declaring `-> impl Trait`, and on `impl` blocks,
+/// applying to each method containing `#[variant]` expressions. Closures tagged with
+/// `#[sum]` inside a `#[sum]` item are expanded by the item's attribute, without nightly
+/// features.
+///
+/// On a trait definition, each required method returning `impl Trait` returns an associated
+/// type instead, e.g. `type Numbers: Iterator<Item=u8>;` for `fn numbers(..) -> impl Iterator<Item=u8>;`.
+/// An implementor tagged with `#[sum]` gives it as a predefined enum, e.g. `type Numbers = Enum2<A,B>;`,
+/// into which the method's `#[variant]` expressions are summed. Provided methods are summed as usual.
 #[proc_macro_attribute]
 pub fn sum( args: TokenStream, input: TokenStream ) -> TokenStream {
-    let SumArgs{ which_impl_trait, mut impl_generics, trait_path, enum_prefix } = parse_macro_input!( args as SumArgs );
-    let mut item_fn = parse_macro_input!( input as ItemFn );
+    let args = parse_macro_input!( args as SumArgs );
+    let mut errors = Errors::default();
+
+    let expanded = match syn::parse::<Item>( input.clone() ) {
+        Ok( Item::Fn( mut item_fn )) => {
+            SumClosure{ errors: &mut errors }.visit_block_mut( &mut item_fn.block );
+            sum_block( &args, &item_fn.sig, &mut item_fn.block, &mut errors );
+            quote!( #item_fn )
+        },
+        Ok( Item::Impl( mut item_impl )) => {
+            // Methods returning an associated type, which is given as a predefined enum, are summed into it.
+            let assoc_args = if item_impl.trait_.is_some() && args.trait_path.is_none() && args.enum_prefix.is_none() {
+                Some( SumArgs{ enum_prefix: Some( make_ident( "Enum" )), ..args.clone() })
+            } else {
+                None
+            };
+            let enum_assoc_types = item_impl.items.iter()
+                .filter_map( |impl_item| match impl_item {
+                    ImplItem::Type( impl_type ) if is_predefined_enum( &impl_type.ty ) => Some( impl_type.ident.clone() ),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for impl_item in &mut item_impl.items {
+                if let ImplItem::Method( method ) = impl_item {
+                    SumClosure{ errors: &mut errors }.visit_block_mut( &mut method.block );
+                    match &assoc_args {
+                        Some( assoc_args ) if returns_self_assoc( &method.sig ).map( |ident| enum_assoc_types.contains( ident )).unwrap_or( false ) =>
+                            sum_block( assoc_args, &method.sig, &mut method.block, &mut errors ),
+                        _ => if !args.auto || returns_impl_trait( &method.sig ) {
+                            sum_block( &args, &method.sig, &mut method.block, &mut errors );
+                        },
+                    }
+                }
+            }
+            quote!( #item_impl )
+        },
+        Ok( Item::Trait( mut item_trait )) => {
+            sum_assoc_types( &mut item_trait, &mut errors );
+            for trait_item in &mut item_trait.items {
+                if let TraitItem::Method( method ) = trait_item {
+                    if let Some( block ) = &mut method.default {
+                        SumClosure{ errors: &mut errors }.visit_block_mut( block );
+                        if !args.auto || returns_impl_trait( &method.sig ) {
+                            sum_block( &args, &method.sig, block, &mut errors );
+                        }
+                    }
+                }
+            }
+            quote!( #item_trait )
+        },
+        _ => {
+            let mut closure = parse_macro_input!( input as ExprClosure );
+            SumClosure{ errors: &mut errors }.visit_expr_mut( &mut closure.body );
+            sum_closure( &args, &mut closure, &mut errors );
+            if errors.is_empty() {
+                quote!( #closure )
+            } else {
+                let errors = errors.to_compile_error();
+                return quote!({ #errors #closure }).into();
+            }
+        },
+    };
+
+    let errors = errors.to_compile_error();
+    quote!( #expanded #errors ).into()
+}
+
+// Sums the closure declaring `-> impl Trait`, the return type of which is removed.
+fn sum_closure( args: &SumArgs, closure: &mut ExprClosure, errors: &mut Errors ) {
+    let sig: Signature = {
+        let output = &closure.output;
+        let asyncness = &closure.asyncness;
+        parse_quote!( #asyncness fn closure() #output )
+    };
+    let mut block = match *closure.body {
+        Expr::Block( ExprBlock{ ref attrs, label: None, ref block }) if attrs.is_empty() => block.clone(),
+        ref body => parse_quote!({ #body }),
+    };
+    sum_block( args, &sig, &mut block, errors );
+    // `impl Trait` is not allowed as the return type of closures.
+    closure.output = ReturnType::Default;
+    *closure.body = parse_quote!( #block );
+}
+
+// Expands closures tagged with `#[sum]` inside an item tagged with `#[sum]`, which needs no nightly features.
+struct SumClosure<'a> {
+    errors : &'a mut Errors,
+}
+
+impl<'a> VisitMut for SumClosure<'a> {
+    fn visit_expr_mut( &mut self, expr: &mut Expr ) {
+        visit_mut::visit_expr_mut( self, expr );
+
+        if let Expr::Closure( closure ) = expr {
+            if let Some( index ) = closure.attrs.iter().position( |attr| attr.path.is_ident( "sum" )) {
+                let attr = closure.attrs.remove( index );
+                let args = if attr.tokens.is_empty() { Ok( SumArgs::default() )} else { attr.parse_args::<SumArgs>() };
+                match args {
+                    Ok( args ) => sum_closure( &args, closure, self.errors ),
+                    Err( error ) => self.errors.push( error ),
+                }
+            }
+        }
+    }
+
+    fn visit_item_mut( &mut self, _item: &mut Item ) {}
+}
+
+// Replaces `-> impl Trait` of the required methods with associated types named after the methods in camel case,
+// e.g. `fn numbers( &self ) -> impl Iterator<Item=u8>;` with `type Numbers: Iterator<Item=u8>;` and
+// `fn numbers( &self ) -> Self::Numbers;`. Methods of generic parameters or borrowing lifetimes are left untouched,
+// because the associated types could not depend on them.
+fn sum_assoc_types( item_trait: &mut ItemTrait, errors: &mut Errors ) {
+    let mut assoc_types = Vec::<TraitItem>::new();
+
+    for trait_item in &mut item_trait.items {
+        if let TraitItem::Method( method ) = trait_item {
+            if method.default.is_some() || !method.sig.generics.params.is_empty() {
+                continue;
+            }
+            let type_impl_trait = match &method.sig.output {
+                ReturnType::Type( _, ty ) => match &**ty {
+                    Type::ImplTrait( type_impl_trait ) => type_impl_trait.clone(),
+                    _ => continue,
+                },
+                ReturnType::Default => continue,
+            };
+            if type_impl_trait.bounds.iter().any( |bound| match bound {
+                TypeParamBound::Lifetime( lifetime ) => lifetime.ident != "static",
+                TypeParamBound::Trait(_) => false,
+            }) {
+                continue;
+            }
+
+            let method_name = method.sig.ident.to_string();
+            let assoc_ident = Ident::new( &method_name
+                .split( '_' )
+                .filter( |word| !word.is_empty() )
+                .map( |word| {
+                    let mut chars = word.chars();
+                    chars.next().map( |first| first.to_uppercase().chain( chars ).collect::<String>() ).unwrap_or_default()
+                })
+                .collect::<String>(), method.sig.ident.span() );
+
+            let bounds = &type_impl_trait.bounds;
+            assoc_types.push( parse_quote!( type #assoc_ident: #bounds; ));
+            method.sig.output = parse_quote!( -> Self::#assoc_ident );
+        }
+    }
+
+    for assoc_type in &assoc_types {
+        if let TraitItem::Type( assoc_type ) = assoc_type {
+            let clashes = item_trait.items.iter().any( |trait_item| match trait_item {
+                TraitItem::Type( existing ) => existing.ident == assoc_type.ident,
+                _ => false,
+            });
+            if clashes {
+                errors.error( &assoc_type.ident, format!( "#[sum]: associated type `{}` is already defined", assoc_type.ident ));
+            }
+        }
+    }
+
+    item_trait.items.splice( 0..0, assoc_types );
+}
+
+// Gets the name of the associated type if the method returns `Self::AssociatedType`.
+fn returns_self_assoc( sig: &Signature ) -> Option<&Ident> {
+    match &sig.output {
+        ReturnType::Type( _, ty ) => match &**ty {
+            Type::Path( type_path ) if type_path.qself.is_none()
+                && type_path.path.segments.len() == 2
+                && type_path.path.segments[0].ident == "Self" => Some( &type_path.path.segments[1].ident ),
+            _ => None,
+        },
+        ReturnType::Default => None,
+    }
+}
+
+// Checks if the type is `Enum!(..)` or one of `Enum1`, `Enum2`, ...
+fn is_predefined_enum( ty: &Type ) -> bool {
+    match ty {
+        Type::Macro( type_macro ) => type_macro.mac.path.is_ident( "Enum" ),
+        Type::Path( type_path ) => type_path.path.segments.last().map( |segment| {
+            let name = segment.ident.to_string();
+            name.len() > 4 && name.starts_with( "Enum" ) && name[4..].bytes().all( |b| b.is_ascii_digit() )
+        }).unwrap_or( false ),
+        _ => false,
+    }
+}
+
+fn returns_impl_trait( sig: &Signature ) -> bool {
+    match &sig.output {
+        ReturnType::Type( _, ty ) => matches!( **ty, Type::ImplTrait(_) ),
+        ReturnType::Default => false,
+    }
+}
+
+// Wraps the `#[variant]` expressions in the fn body with an enum which implements
+// the traits in the fn's signature. Leaves the body untouched if there is no variant.
+fn sum_block( args: &SumArgs, sig: &Signature, fn_block: &mut Block, errors: &mut Errors ) {
+    if args.auto {
+        SumAuto.visit_block_mut( fn_block );
+        SumAuto::tag_block( fn_block );
+    }
 
-    let mut sum = Sum::new( which_impl_trait );
-    sum.visit_block_mut( &mut item_fn.block );
+    let mut sum = Sum::new( args.which_impl_trait.clone() );
+    sum.visit_block_mut( fn_block );
+    errors.extend( sum.errors );
 
     let variant_count = sum.variant_count;
+    if variant_count == 0 {
+        return;
+    }
 
     let (enum_basename, enum_ident);
     let enum_def;
-    let enum_impl: Option<ExprMacro>;
+    let enum_impls: Vec<ExprMacro>;
 
-    match enum_prefix {
+    match &args.enum_prefix {
         None => {
             enum_basename = make_ident( "__SumType" );
             enum_ident = make_ident( &format!( "__SumType{}", variant_count ));
             let enum_basename = &enum_basename;
 
-            let trait_path = trait_path.unwrap_or_else( || loop {
-                let type_impl_trait = loop {
-                    if let ReturnType::Type( _, ty ) = &item_fn.sig.output {
-                        if let Type::ImplTrait( it ) = &**ty {
-                            break it.clone();
+            let trait_paths = match &args.trait_path {
+                Some( trait_path ) => vec![ ( args.impl_generics.clone(), trait_path.clone() )],
+                None => {
+                    let type_impl_trait = loop {
+                        if let ReturnType::Type( _, ty ) = &sig.output {
+                            if let Type::ImplTrait( it ) = &**ty {
+                                break Some( it.clone() );
+                            }
                         }
-                    }
-                    panic!("#[sum] fn should return `impl Trait`");
-                };
+                        if sig.asyncness.is_some() {
+                            break None;
+                        }
+                        // The enum is still defined, without trait implementations.
+                        match &sig.output {
+                            ReturnType::Type( _, ty ) => errors.error( ty, "#[sum] fn should return `impl Trait`" ),
+                            ReturnType::Default => errors.error( &sig.ident, "#[sum] fn should return `impl Trait`" ),
+                        }
+                        break None;
+                    };
 
-                let mut iter = type_impl_trait.bounds.iter();
-                match iter.next() {
-                    Some( bound ) => if let TypeParamBound::Trait( trait_bound ) = bound {
-                        let mut path = trait_bound.path.clone();
-                        path.segments.last_mut().map( |path_seg| {
-                            match path_seg.ident.to_string().as_str() {
-                                "Fn" | "FnMut" | "FnOnce" => {
-                                    path_seg.arguments = PathArguments::AngleBracketed( parse_quote_spanned!( path_seg.arguments.span() => <Args> ));
-                                    let mut punct = Punctuated::new();
-                                    punct.push( parse_quote_spanned!( path_seg.arguments.span() => Args ));
-                                    impl_generics = Some( punct );
-                                },
-                                _ => {
-                                    path_seg.arguments = PathArguments::None;
-                                },
+                    match type_impl_trait {
+                        Some( type_impl_trait ) => {
+                            let trait_paths = type_impl_trait.bounds.iter()
+                                .filter_map( |bound| match bound {
+                                    TypeParamBound::Trait( trait_bound ) => sum_trait_path( trait_bound ),
+                                    TypeParamBound::Lifetime( _ ) => None,
+                                })
+                                .collect::<Vec<_>>();
+                            if trait_paths.is_empty() {
+                                errors.error( &type_impl_trait, "#[sum]: expected trait bound after `-> impl`" );
                             }
-                        });
-                        break path;
-                    },
-                    None => panic!("#[sum]: expected trait bound after `-> impl`"),
-                }
-            });
+                            trait_paths
+                        },
+                        None if sig.asyncness.is_some() => vec![ ( None, parse_quote!( std::future::Future ))],
+                        None => Vec::new(),
+                    }
+                },
+            };
             enum_def = Some( quote!( enum #enum_basename![#variant_count..=#variant_count]; ));
-            enum_impl = Some( parse_quote!( impl_all_traits!{ _impl!(#impl_generics) #trait_path _for!( #enum_basename![#variant_count..=#variant_count] )}));
+            enum_impls = trait_paths.into_iter().map( |( impl_generics, trait_path )| parse_quote!(
+                impl_all_traits!{ _impl!(#impl_generics) #trait_path _for!( #enum_basename![#variant_count..=#variant_count] )}
+            )).collect();
         },
         Some( enum_prefix ) => {
             enum_ident = make_ident( &format!( "{}{}", enum_prefix.to_string(), variant_count ));
             enum_def = None;
-            enum_impl = None;
+            enum_impls = Vec::new();
         },
     }
 
     let enum_def = match enum_def {
         Some( enum_def ) => {
             let token_stream = def_impls( enum_def.into() );
-            Some( parse_macro_input!( token_stream as ItemEnum ))
+            syn::parse::<syn::File>( token_stream ).expect("enum definition for #[sum]").items
         },
-        None => None,
+        None => Vec::new(),
     };
 
     let placeholder = make_ident( "__SumType" );
-    ReplaceIdent::new( placeholder, enum_ident ).visit_block_mut( &mut item_fn.block );
+    ReplaceIdent::new( placeholder, enum_ident ).visit_block_mut( fn_block );
 
     let mut block = parse_quote!({});
-    mem::swap( &mut block, &mut item_fn.block );
-    item_fn.block = parse_quote_spanned! { block.span() => {
-        #enum_def
-        #enum_impl
+    mem::swap( &mut block, fn_block );
+    *fn_block = parse_quote_spanned! { block.span() => {
+        #(#enum_def)*
+        #(#enum_impls)*
         #block
     }};
-
-    let expanded = quote!( #item_fn );
-    expanded.into()
 }
 
 struct SumErr;
@@ -1604,3 +3123,239 @@ pub fn sum_err( _args: TokenStream, input: TokenStream ) -> TokenStream {
     let expanded = quote!( #item_fn );
     expanded.into()
 }
+
+/// Generates a companion macro for a trait, so that
+/// `impl_trait!{ path::to::Trait _for!( Enum![1..=16] )}` implements it for enums by
+/// forwarding methods to the variants.
+///
+/// ## Example
+///
+/// ```text
+/// #[enumx::delegate]
+/// trait Shape {
+///     fn area( &self ) -> f64;
+/// }
+///
+/// impl_trait!{ Shape _for!( Enum![1..=16] )}
+/// ```
+///
+/// Methods with `self`, `&self`, `&mut self` or `self: Pin<&mut Self>` receivers are
+/// forwarded, and `Self` as the return type is wrapped back into the enum. Associated
+/// types are required to be the same in all variants. Associated consts without default
+/// values are rejected, unless `#[enumx::delegate( first_variant_consts )]` takes them
+/// from the first variant.
+///
+/// The companion macro of a non-private trait is `#[macro_export]`ed under a hidden name,
+/// and re-exported with the trait's visibility.
+#[proc_macro_attribute]
+pub fn delegate( args: TokenStream, input: TokenStream ) -> TokenStream {
+    let first_variant_consts = match syn::parse::<Option<Ident>>( args ) {
+        Ok( None ) => false,
+        Ok( Some( ident )) if ident == "first_variant_consts" => true,
+        Ok( Some( ident )) => return syn::Error::new( ident.span(), "#[delegate( first_variant_consts )] expected" ).to_compile_error().into(),
+        Err( err ) => return err.to_compile_error().into(),
+    };
+    let item_trait = parse_macro_input!( input as ItemTrait );
+    let trait_ident = &item_trait.ident;
+    let macro_ident = make_ident( &format!( "__enumx_delegate_{}", trait_ident ));
+    let mut errors = Errors::default();
+
+    let mut lifetime_params = Vec::<proc_macro2::TokenStream>::new();
+    let mut impl_params = Vec::<proc_macro2::TokenStream>::new();
+    let mut trait_args = Vec::<proc_macro2::TokenStream>::new();
+
+    for param in &item_trait.generics.params {
+        match param {
+            GenericParam::Lifetime( lifetime_def ) => {
+                let lifetime = &lifetime_def.lifetime;
+                let bounds = &lifetime_def.bounds;
+                lifetime_params.push( quote!( #lifetime: #bounds ));
+                trait_args.push( quote!( #lifetime ));
+            },
+            GenericParam::Type( type_param ) => {
+                let ident = &type_param.ident;
+                let bounds = &type_param.bounds;
+                impl_params.push( quote!( #ident: #bounds ));
+                trait_args.push( quote!( #ident ));
+            },
+            GenericParam::Const( const_param ) => {
+                let ident = &const_param.ident;
+                let ty = &const_param.ty;
+                impl_params.push( quote!( const #ident: #ty ));
+                trait_args.push( quote!( #ident ));
+            },
+        }
+    }
+
+    let trait_generics = if trait_args.is_empty() { quote!() } else { quote!( <#(#trait_args),*> )};
+
+    let mut bindings = Vec::<proc_macro2::TokenStream>::new();
+    let mut items = Vec::<proc_macro2::TokenStream>::new();
+
+    for trait_item in &item_trait.items {
+        match trait_item {
+            TraitItem::Type( trait_item_type ) => {
+                let ident = &trait_item_type.ident;
+                if !trait_item_type.generics.params.is_empty() {
+                    errors.error( ident, format!( "#[delegate]: generic associated type `{}` is not supported", ident ));
+                    continue;
+                }
+                let param = make_ident( &format!( "_{}", ident ));
+                let bounds = &trait_item_type.bounds;
+                impl_params.push( quote!( #param: #bounds ));
+                bindings.push( quote!( #ident = #param ));
+                items.push( quote!( type #ident = #param; ));
+            },
+            TraitItem::Const( trait_item_const ) if trait_item_const.default.is_none() => {
+                let ident = &trait_item_const.ident;
+                let ty = &trait_item_const.ty;
+                if !first_variant_consts {
+                    errors.error( ident, format!( "#[delegate]: associated const `{}` may differ in variants, use #[delegate( first_variant_consts )] to take it from the first variant", ident ));
+                    continue;
+                }
+                items.push( quote!(
+                    const #ident: #ty = <_Variant!() as $($trait_)+ #trait_generics>::#ident;
+                ));
+            },
+            TraitItem::Method( trait_item_method ) => {
+                let mut sig = trait_item_method.sig.clone();
+                let method_ident = &sig.ident;
+
+                let mut pinned = false;
+                match sig.inputs.first_mut() {
+                    Some( FnArg::Receiver(_) ) => (),
+                    Some( FnArg::Typed( pat_type )) if is_self_pat( &pat_type.pat ) => {
+                        if !is_pin_mut_self( &pat_type.ty ) {
+                            errors.error( &pat_type, format!( "#[delegate]: the receiver of method `{}` is not supported", method_ident ));
+                            continue;
+                        }
+                        pat_type.ty = parse_quote!( core::pin::Pin<&mut Self> );
+                        pinned = true;
+                    },
+                    _ => if trait_item_method.default.is_some() {
+                        continue;
+                    } else {
+                        errors.error( &trait_item_method.sig, format!( "#[delegate]: method `{}` without `self` receiver can not be forwarded", method_ident ));
+                        continue;
+                    },
+                }
+
+                let mut args = Vec::<Ident>::new();
+                for ( index, fn_arg ) in sig.inputs.iter_mut().skip(1).enumerate() {
+                    if let FnArg::Typed( pat_type ) = fn_arg {
+                        let arg = make_ident( &format!( "__arg{}", index ));
+                        pat_type.attrs.clear();
+                        pat_type.pat = parse_quote!( #arg );
+                        args.push( arg );
+                    }
+                }
+
+                let receiver = if pinned {
+                    quote!( unsafe{ core::pin::Pin::new_unchecked( _variant!() )})
+                } else {
+                    quote!( _variant!() )
+                };
+                let mut forwarded = quote!( <_Variant!() as $($trait_)+ #trait_generics>::#method_ident( #receiver #(, #args )* ));
+                if sig.asyncness.is_some() {
+                    forwarded = quote!( #forwarded.await );
+                }
+                if let ReturnType::Type( _, ty ) = &sig.output {
+                    if let Type::Path( type_path ) = &**ty {
+                        if type_path.qself.is_none() && type_path.path.is_ident( "Self" ) {
+                            forwarded = quote!( _enum!( #forwarded ));
+                        }
+                    }
+                }
+                if pinned {
+                    forwarded = quote!( unsafe{ self.get_unchecked_mut() } => #forwarded );
+                }
+
+                items.push( quote!( #sig { _match!( #forwarded )} ));
+            },
+            _ => (),
+        }
+    }
+
+    let where_bound = if trait_args.is_empty() && bindings.is_empty() {
+        quote!()
+    } else {
+        quote!( <#(#trait_args,)* #(#bindings),*> )
+    };
+    let trait_predicates = item_trait.generics.where_clause.as_ref().map( |where_clause| {
+        let predicates = where_clause.predicates.iter();
+        quote!( #(, #predicates )* )
+    });
+
+    if !errors.is_empty() {
+        let errors = errors.to_compile_error();
+        return quote!( #item_trait #errors ).into();
+    }
+
+    // A non-private trait's companion macro is exported, under a name unique in the crate, to be
+    // re-exported along with the trait.
+    let ( macro_export, macro_ident ) = match &item_trait.vis {
+        Visibility::Inherited => ( quote!(), macro_ident ),
+        _ => {
+            let mut hasher = DefaultHasher::new();
+            format!( "{:?}", trait_ident.span() ).hash( &mut hasher );
+            quote!( #item_trait ).to_string().hash( &mut hasher );
+            ( quote!( #[macro_export] ), make_ident( &format!( "{}_{:016x}", macro_ident, hasher.finish() )))
+        },
+    };
+    let vis = &item_trait.vis;
+
+    let expanded = quote! {
+        #item_trait
+
+        #[doc( hidden )]
+        #[allow( unused_macros )]
+        #macro_export
+        macro_rules! #macro_ident {
+            (_super_traits!( $($trait_:tt)+ ) $($_tt:tt)*) => {};
+            (_trait!( $($trait_:tt)+ ) $(_impl!($($gen:tt),*))* _for!($($ty:tt)+) $(_where!($($pred:tt)*))*) => {
+                def_impls! {
+                    impl<#(#lifetime_params,)* $($($gen,)*)* #(#impl_params),*> $($trait_)+ #trait_generics for $($ty)+
+                        where _Variants!(): $($trait_)+ #where_bound
+                              #trait_predicates
+                              $($(, $pred)*)*
+                    {
+                        #(#items)*
+                    }
+                }
+            };
+        }
+
+        #[doc( hidden )]
+        #[allow( unused_imports )]
+        #vis use #macro_ident as #trait_ident;
+    };
+    expanded.into()
+}
+
+fn is_self_pat( pat: &Pat ) -> bool {
+    if let Pat::Ident( pat_ident ) = pat {
+        pat_ident.ident == "self"
+    } else {
+        false
+    }
+}
+
+// Checks if the type is `Pin<&mut Self>`.
+fn is_pin_mut_self( ty: &Type ) -> bool {
+    if let Type::Path( type_path ) = ty {
+        if let Some( path_seg ) = type_path.path.segments.last() {
+            if path_seg.ident == "Pin" {
+                if let PathArguments::AngleBracketed( args ) = &path_seg.arguments {
+                    if let Some( GenericArgument::Type( Type::Reference( type_ref ))) = args.args.first() {
+                        if type_ref.mutability.is_some() {
+                            if let Type::Path( inner ) = &*type_ref.elem {
+                                return inner.path.is_ident( "Self" );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    false
+}